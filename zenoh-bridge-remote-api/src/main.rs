@@ -68,14 +68,19 @@ struct Args {
     #[arg(long, value_name = "PORT | IP:PORT")]
     ws_port: Option<String>,
 
-    /// Path to the TLS certificate file for secure WebSocket connections.
-    #[arg(long, value_name = "PATH")]
+    /// Path to the TLS certificate file for secure WebSocket connections. Requires `--key`.
+    #[arg(long, value_name = "PATH", requires = "key")]
     cert: Option<String>,
 
-    /// Path to the TLS private key file for secure WebSocket connections.
-    #[arg(long, value_name = "PATH")]
+    /// Path to the TLS private key file for secure WebSocket connections. Requires `--cert`.
+    #[arg(long, value_name = "PATH", requires = "cert")]
     key: Option<String>,
 
+    /// Path to the CA certificate bundle used to verify client certificates (mutual TLS).
+    /// Requires `--cert` and `--key`.
+    #[arg(long, value_name = "PATH", requires = "cert", requires = "key")]
+    client_ca: Option<String>,
+
     /// Allows arbitrary configuration changes as colon-separated KEY:VALUE pairs,
     /// where the empty key is used to represent the entire configuration:
     ///   - KEY must be a valid config path, or empty string if the whole configuration is defined.
@@ -241,6 +246,14 @@ fn parse_args() -> Config {
                     &format!(r#""{key_path}""#),
                 )
                 .unwrap();
+            if let Some(client_ca_path) = &args.client_ca {
+                config
+                    .insert_json5(
+                        "plugins/remote_api/secure_websocket/client_auth/ca_certificate_path",
+                        &format!(r#""{client_ca_path}""#),
+                    )
+                    .unwrap();
+            }
         }
     }
 
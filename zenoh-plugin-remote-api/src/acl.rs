@@ -0,0 +1,295 @@
+//
+// Copyright (c) 2026 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::fmt;
+
+use zenoh::key_expr::{keyexpr, OwnedKeyExpr};
+use zenoh_result::{zerror, ZResult};
+
+use crate::config::{AccessControl, AccessControlAction, Permission};
+
+impl fmt::Display for AccessControlAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessControlAction::Publish => write!(f, "publish"),
+            AccessControlAction::Subscribe => write!(f, "subscribe"),
+            AccessControlAction::Query => write!(f, "query"),
+            AccessControlAction::DeclareQueryable => write!(f, "declare queryable"),
+            AccessControlAction::DeclareLivelinessToken => write!(f, "declare liveliness token"),
+        }
+    }
+}
+
+struct Rule {
+    permission: Permission,
+    actions: Vec<AccessControlAction>,
+    key_exprs: Vec<OwnedKeyExpr>,
+    users: Vec<String>,
+    cert_subjects: Vec<String>,
+}
+
+impl Rule {
+    fn applies_to(&self, user: Option<&str>, cert_subject: Option<&str>) -> bool {
+        (self.users.is_empty() && self.cert_subjects.is_empty())
+            || user.is_some_and(|user| self.users.iter().any(|u| u == user))
+            || cert_subject.is_some_and(|subject| self.cert_subjects.iter().any(|s| s == subject))
+    }
+}
+
+/// The access control rules of the plugin, with validated key expressions.
+pub struct Acl {
+    default_permission: Permission,
+    rules: Vec<Rule>,
+}
+
+impl Acl {
+    pub(crate) fn new(config: &AccessControl) -> ZResult<Self> {
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| {
+                let key_exprs = rule
+                    .key_exprs
+                    .iter()
+                    .map(|ke| {
+                        OwnedKeyExpr::autocanonize(ke.clone())
+                            .map_err(|e| zerror!("Invalid key expression '{}' in rule: {}", ke, e))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Rule {
+                    permission: rule.permission,
+                    actions: rule.actions.clone(),
+                    key_exprs,
+                    users: rule.users.clone(),
+                    cert_subjects: rule.cert_subjects.clone(),
+                })
+            })
+            .collect::<ZResult<Vec<_>>>()?;
+        Ok(Acl {
+            default_permission: config.default_permission,
+            rules,
+        })
+    }
+
+    /// Returns the rules applying to a client, identified by its authenticated user
+    /// and the subject of its verified certificate.
+    pub(crate) fn client_acl(&self, user: Option<&str>, cert_subject: Option<&str>) -> ClientAcl {
+        let mut allow = Vec::new();
+        let mut deny = Vec::new();
+        for rule in self
+            .rules
+            .iter()
+            .filter(|r| r.applies_to(user, cert_subject))
+        {
+            let entries = match rule.permission {
+                Permission::Allow => &mut allow,
+                Permission::Deny => &mut deny,
+            };
+            for action in &rule.actions {
+                for ke in &rule.key_exprs {
+                    entries.push((*action, ke.clone()));
+                }
+            }
+        }
+        ClientAcl {
+            default_permission: self.default_permission,
+            allow,
+            deny,
+            read_only: false,
+        }
+    }
+}
+
+/// The access control rules applying to a single client.
+pub(crate) struct ClientAcl {
+    default_permission: Permission,
+    allow: Vec<(AccessControlAction, OwnedKeyExpr)>,
+    deny: Vec<(AccessControlAction, OwnedKeyExpr)>,
+    read_only: bool,
+}
+
+impl ClientAcl {
+    /// The rules applying to the clients when no access control is configured.
+    pub(crate) fn allow_all() -> Self {
+        ClientAcl {
+            default_permission: Permission::Allow,
+            allow: Vec::new(),
+            deny: Vec::new(),
+            read_only: false,
+        }
+    }
+
+    /// Only allows the client to subscribe and to query, whatever the rules.
+    pub(crate) fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// A request is denied as soon as any of its matching keys is denied,
+    /// and it is only allowed by a rule covering all of them.
+    pub(crate) fn permission(&self, action: AccessControlAction, key_expr: &keyexpr) -> Permission {
+        let reads = matches!(
+            action,
+            AccessControlAction::Subscribe | AccessControlAction::Query
+        );
+        if (self.read_only && !reads)
+            || self
+                .deny
+                .iter()
+                .any(|(a, ke)| *a == action && ke.intersects(key_expr))
+        {
+            Permission::Deny
+        } else if self
+            .allow
+            .iter()
+            .any(|(a, ke)| *a == action && ke.includes(key_expr))
+        {
+            Permission::Allow
+        } else {
+            self.default_permission
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zenoh::key_expr::keyexpr;
+
+    use super::Acl;
+    use crate::config::{AccessControl, AccessControlAction, AccessControlRule, Permission};
+
+    fn acl(default_permission: Permission, rules: Vec<AccessControlRule>) -> Acl {
+        Acl::new(&AccessControl {
+            default_permission,
+            rules,
+        })
+        .unwrap()
+    }
+
+    fn rule(permission: Permission, key_expr: &str, users: &[&str]) -> AccessControlRule {
+        AccessControlRule {
+            permission,
+            actions: vec![AccessControlAction::Subscribe],
+            key_exprs: vec![key_expr.to_string()],
+            users: users.iter().map(|u| u.to_string()).collect(),
+            cert_subjects: Vec::new(),
+        }
+    }
+
+    fn ke(s: &str) -> &keyexpr {
+        keyexpr::new(s).unwrap()
+    }
+
+    #[test]
+    fn test_allow_requires_inclusion() {
+        let acl = acl(
+            Permission::Deny,
+            vec![rule(Permission::Allow, "demo/**", &[])],
+        );
+        let client = acl.client_acl(None, None);
+        let sub = AccessControlAction::Subscribe;
+        assert_eq!(client.permission(sub, ke("demo/a/b")), Permission::Allow);
+        assert_eq!(client.permission(sub, ke("demo/*")), Permission::Allow);
+        assert_eq!(client.permission(sub, ke("**")), Permission::Deny);
+        assert_eq!(
+            client.permission(sub, ke("demonstration")),
+            Permission::Deny
+        );
+        assert_eq!(
+            client.permission(AccessControlAction::Publish, ke("demo/a")),
+            Permission::Deny
+        );
+    }
+
+    #[test]
+    fn test_deny_on_intersection() {
+        let acl = acl(
+            Permission::Allow,
+            vec![rule(Permission::Deny, "demo/secret/**", &[])],
+        );
+        let client = acl.client_acl(None, None);
+        let sub = AccessControlAction::Subscribe;
+        assert_eq!(client.permission(sub, ke("demo/public")), Permission::Allow);
+        assert_eq!(client.permission(sub, ke("demo/secret")), Permission::Deny);
+        assert_eq!(client.permission(sub, ke("demo/*/x")), Permission::Deny);
+        assert_eq!(client.permission(sub, ke("**")), Permission::Deny);
+    }
+
+    #[test]
+    fn test_rule_users() {
+        let acl = acl(
+            Permission::Deny,
+            vec![rule(Permission::Allow, "demo/**", &["alice"])],
+        );
+        let sub = AccessControlAction::Subscribe;
+        assert_eq!(
+            acl.client_acl(Some("alice"), None)
+                .permission(sub, ke("demo/a")),
+            Permission::Allow
+        );
+        assert_eq!(
+            acl.client_acl(Some("bob"), None)
+                .permission(sub, ke("demo/a")),
+            Permission::Deny
+        );
+        assert_eq!(
+            acl.client_acl(None, None).permission(sub, ke("demo/a")),
+            Permission::Deny
+        );
+    }
+
+    #[test]
+    fn test_rule_cert_subjects() {
+        let mut allow = rule(Permission::Allow, "demo/**", &["alice"]);
+        allow.cert_subjects = vec!["CN=client1".to_string()];
+        let acl = acl(Permission::Deny, vec![allow]);
+        let sub = AccessControlAction::Subscribe;
+        assert_eq!(
+            acl.client_acl(None, Some("CN=client1"))
+                .permission(sub, ke("demo/a")),
+            Permission::Allow
+        );
+        assert_eq!(
+            acl.client_acl(Some("alice"), Some("CN=client2"))
+                .permission(sub, ke("demo/a")),
+            Permission::Allow
+        );
+        assert_eq!(
+            acl.client_acl(None, Some("CN=client2"))
+                .permission(sub, ke("demo/a")),
+            Permission::Deny
+        );
+    }
+
+    #[test]
+    fn test_read_only() {
+        let client = super::ClientAcl::allow_all().read_only(true);
+        assert_eq!(
+            client.permission(AccessControlAction::Subscribe, ke("demo/a")),
+            Permission::Allow
+        );
+        assert_eq!(
+            client.permission(AccessControlAction::Query, ke("demo/a")),
+            Permission::Allow
+        );
+        assert_eq!(
+            client.permission(AccessControlAction::Publish, ke("demo/a")),
+            Permission::Deny
+        );
+        assert_eq!(
+            client.permission(AccessControlAction::DeclareQueryable, ke("demo/a")),
+            Permission::Deny
+        );
+    }
+}
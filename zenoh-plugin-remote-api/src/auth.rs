@@ -0,0 +1,418 @@
+//
+// Copyright (c) 2026 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
+
+use tokio_tungstenite::tungstenite::{
+    handshake::server::{ErrorResponse, Request},
+    http::{header, StatusCode},
+};
+use zenoh_result::{zerror, ZResult};
+
+use crate::{config::Authentication, spawn_future};
+
+/// How often the tokens file is checked for modifications.
+pub(crate) const TOKENS_FILE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A token accepted by the plugin, and the user it identifies.
+#[derive(Debug, Clone)]
+struct Token {
+    user: String,
+    token: String,
+}
+
+struct TokensFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    tokens: Vec<Token>,
+}
+
+impl TokensFile {
+    fn load(path: PathBuf) -> ZResult<Self> {
+        let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+        let tokens = parse_tokens_file(&path)?;
+        Ok(TokensFile {
+            path,
+            modified,
+            tokens,
+        })
+    }
+
+    /// Re-reads the file if it was modified since it was last loaded, returning whether
+    /// the tokens changed. If the file can't be parsed the previously loaded tokens are
+    /// kept, but if it is gone they are all revoked.
+    fn refresh(&mut self) -> bool {
+        let modified = match fs::metadata(&self.path).and_then(|m| m.modified()) {
+            Ok(modified) => modified,
+            Err(e) => {
+                let revoked = !self.tokens.is_empty();
+                if revoked {
+                    tracing::warn!(
+                        "Unable to access tokens file {:?}, revoking its tokens: {e}",
+                        self.path
+                    );
+                }
+                self.tokens.clear();
+                self.modified = None;
+                return revoked;
+            }
+        };
+        if self.modified == Some(modified) {
+            return false;
+        }
+        match parse_tokens_file(&self.path) {
+            Ok(tokens) => {
+                tracing::info!("Reloaded tokens file {:?}", self.path);
+                self.tokens = tokens;
+                self.modified = Some(modified);
+                true
+            }
+            Err(e) => {
+                tracing::warn!("{e}");
+                false
+            }
+        }
+    }
+}
+
+/// Parses a file containing one `<user>:<token>` entry per line.
+/// Empty lines and lines starting with `#` are ignored.
+fn parse_tokens_file(path: &PathBuf) -> ZResult<Vec<Token>> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| zerror!("Unable to read tokens file {:?}: {e}", path))?;
+    let mut tokens = Vec::new();
+    for (n, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once(':') {
+            Some((user, token)) if !token.is_empty() => tokens.push(Token {
+                user: user.to_string(),
+                token: token.to_string(),
+            }),
+            _ => {
+                return Err(zerror!(
+                    "Invalid entry at line {} of tokens file {:?}, expected `<user>:<token>`",
+                    n + 1,
+                    path
+                )
+                .into())
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Compares two tokens in a time independent of the position of the first difference.
+fn tokens_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+/// Validates the bearer tokens presented by WebSocket clients during the upgrade request.
+pub struct Authenticator {
+    tokens: Vec<Token>,
+    /// The tokens last loaded from the tokens file, swapped by the task watching it.
+    file_tokens: Arc<RwLock<Arc<Vec<Token>>>>,
+    /// The tokens file, until it is handed over to the task watching it.
+    tokens_file: Option<TokensFile>,
+    query_parameter: String,
+}
+
+impl Authenticator {
+    pub(crate) fn new(config: &Authentication) -> ZResult<Self> {
+        let tokens_file = match &config.tokens_file {
+            Some(path) => Some(TokensFile::load(PathBuf::from(path))?),
+            None => None,
+        };
+        let file_tokens = tokens_file
+            .as_ref()
+            .map_or_else(Vec::new, |f| f.tokens.clone());
+        let tokens = config
+            .tokens
+            .iter()
+            .map(|t| Token {
+                user: t.user.clone(),
+                token: t.token.clone(),
+            })
+            .collect::<Vec<_>>();
+        if tokens.is_empty() && tokens_file.is_none() {
+            tracing::warn!("Authentication is enabled but no token is configured, all clients will be rejected");
+        }
+        Ok(Authenticator {
+            tokens,
+            file_tokens: Arc::new(RwLock::new(Arc::new(file_tokens))),
+            tokens_file,
+            query_parameter: config.query_parameter.clone(),
+        })
+    }
+
+    /// Spawns a task polling the tokens file and reloading it when it is modified,
+    /// so that the handshakes never wait on the file system.
+    /// The task stops when the authenticator is dropped.
+    pub(crate) fn watch(&mut self, interval: Duration) {
+        let Some(mut tokens_file) = self.tokens_file.take() else {
+            return;
+        };
+        let file_tokens = Arc::downgrade(&self.file_tokens);
+        spawn_future(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let Some(file_tokens) = file_tokens.upgrade() else {
+                    break;
+                };
+                if tokens_file.refresh() {
+                    *file_tokens.write().unwrap() = Arc::new(tokens_file.tokens.clone());
+                }
+            }
+        });
+    }
+
+    /// Returns the bearer token of the request, taken from the `Authorization` header
+    /// or, since browsers can't set headers on WebSockets, from the query parameter.
+    fn request_token(&self, request: &Request) -> Option<String> {
+        if let Some(value) = request.headers().get(header::AUTHORIZATION) {
+            let value = value.to_str().ok()?;
+            let (scheme, token) = value.split_once(' ')?;
+            return scheme
+                .eq_ignore_ascii_case("bearer")
+                .then(|| token.trim().to_string());
+        }
+        let query = request.uri().query()?;
+        form_urlencoded::parse(query.as_bytes())
+            .find(|(name, _)| name == self.query_parameter.as_str())
+            .map(|(_, value)| value.into_owned())
+    }
+
+    fn find_user(&self, token: &str) -> Option<String> {
+        if let Some(t) = self.tokens.iter().find(|t| tokens_eq(&t.token, token)) {
+            return Some(t.user.clone());
+        }
+        let file_tokens = self.file_tokens.read().unwrap().clone();
+        file_tokens
+            .iter()
+            .find(|t| tokens_eq(&t.token, token))
+            .map(|t| t.user.clone())
+    }
+
+    /// Returns the user identified by the token of the request, if any.
+    pub(crate) fn authenticate(&self, request: &Request) -> Option<String> {
+        self.find_user(&self.request_token(request)?)
+    }
+}
+
+/// The response sent to clients failing authentication.
+pub(crate) fn unauthorized(reason: &str) -> ErrorResponse {
+    let mut response = ErrorResponse::new(Some(reason.to_string()));
+    *response.status_mut() = StatusCode::UNAUTHORIZED;
+    response.headers_mut().insert(
+        header::WWW_AUTHENTICATE,
+        header::HeaderValue::from_static("Bearer"),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        path::PathBuf,
+        time::{Duration, SystemTime},
+    };
+
+    use tokio_tungstenite::tungstenite::{handshake::server::Request, http::header};
+    use uuid::Uuid;
+
+    use super::{parse_tokens_file, tokens_eq, Authenticator, TokensFile};
+    use crate::config::{Authentication, UserToken};
+
+    fn tokens_file(content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("remote-api-tokens-{}", Uuid::new_v4()));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    /// Rewrites a tokens file, with a modification time which can't be mistaken for the previous one.
+    fn rewrite(path: &PathBuf, content: &str, modified: SystemTime) {
+        fs::write(path, content).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_tokens_eq() {
+        assert!(tokens_eq("secret", "secret"));
+        assert!(tokens_eq("", ""));
+        assert!(!tokens_eq("secret", "secreT"));
+        assert!(!tokens_eq("secret", "secret2"));
+        assert!(!tokens_eq("secret", ""));
+    }
+
+    #[test]
+    fn test_parse_tokens_file() {
+        let path = tokens_file("# users\n\nalice:secret\n  bob:p:ss  \n");
+        let tokens = parse_tokens_file(&path).unwrap();
+        let tokens: Vec<_> = tokens
+            .iter()
+            .map(|t| (t.user.as_str(), t.token.as_str()))
+            .collect();
+        assert_eq!(tokens, [("alice", "secret"), ("bob", "p:ss")]);
+
+        rewrite(&path, "alice:secret\ncarol\n", SystemTime::now());
+        let err = parse_tokens_file(&path).unwrap_err().to_string();
+        assert!(err.contains("line 2"), "{err}");
+        rewrite(&path, "dave:\n", SystemTime::now());
+        assert!(parse_tokens_file(&path).is_err());
+
+        fs::remove_file(&path).unwrap();
+        assert!(parse_tokens_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_tokens_file_refresh() {
+        let path = tokens_file("alice:one\n");
+        let mut file = TokensFile::load(path.clone()).unwrap();
+        assert_eq!(file.tokens[0].token, "one");
+
+        let later = SystemTime::now() + Duration::from_secs(10);
+        assert!(!file.refresh());
+        rewrite(&path, "alice:two\n", later);
+        assert!(file.refresh());
+        assert_eq!(file.tokens[0].token, "two");
+
+        // an invalid file keeps the tokens previously loaded
+        rewrite(&path, "alice\n", later + Duration::from_secs(10));
+        assert!(!file.refresh());
+        assert_eq!(file.tokens[0].token, "two");
+
+        // a missing one revokes them
+        fs::remove_file(&path).unwrap();
+        assert!(file.refresh());
+        assert!(file.tokens.is_empty());
+        assert!(!file.refresh());
+        rewrite(&path, "alice:three\n", later + Duration::from_secs(20));
+        assert!(file.refresh());
+        assert_eq!(file.tokens[0].token, "three");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_authenticate() {
+        let path = tokens_file("bob:from-file\n");
+        let authenticator = Authenticator::new(&Authentication {
+            tokens: vec![UserToken {
+                user: "alice".to_string(),
+                token: "secret".to_string(),
+            }],
+            tokens_file: Some(path.to_string_lossy().into_owned()),
+            query_parameter: "access_token".to_string(),
+        })
+        .unwrap();
+        let request = |uri: &str, authorization: Option<&str>| {
+            let mut request = Request::builder().uri(uri);
+            if let Some(authorization) = authorization {
+                request = request.header(header::AUTHORIZATION, authorization);
+            }
+            request.body(()).unwrap()
+        };
+        let authenticate =
+            |uri, authorization| authenticator.authenticate(&request(uri, authorization));
+
+        // from the header
+        assert_eq!(
+            authenticate("/", Some("Bearer secret")).as_deref(),
+            Some("alice")
+        );
+        assert_eq!(
+            authenticate("/", Some("bearer from-file ")).as_deref(),
+            Some("bob")
+        );
+        assert_eq!(authenticate("/", Some("Basic secret")), None);
+        assert_eq!(authenticate("/", Some("Bearer wrong")), None);
+        // the header takes precedence over the query parameter
+        assert_eq!(
+            authenticate("/?access_token=secret", Some("Bearer wrong")),
+            None
+        );
+
+        // from the query parameter
+        assert_eq!(
+            authenticate("/?resume&access_token=secret", None).as_deref(),
+            Some("alice")
+        );
+        assert_eq!(
+            authenticate("/?access_token=from-file", None).as_deref(),
+            Some("bob")
+        );
+        assert_eq!(authenticate("/?token=secret", None), None);
+        assert_eq!(authenticate("/", None), None);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_tokens_file_watch() {
+        let path = tokens_file("bob:one\n");
+        let mut authenticator = Authenticator::new(&Authentication {
+            tokens: vec![UserToken {
+                user: "alice".to_string(),
+                token: "secret".to_string(),
+            }],
+            tokens_file: Some(path.to_string_lossy().into_owned()),
+            query_parameter: "access_token".to_string(),
+        })
+        .unwrap();
+        authenticator.watch(Duration::from_millis(10));
+        let authenticate = |token: &str| {
+            let request = Request::builder()
+                .uri("/")
+                .header(header::AUTHORIZATION, format!("Bearer {token}"))
+                .body(())
+                .unwrap();
+            authenticator.authenticate(&request)
+        };
+        let wait = || tokio::time::sleep(Duration::from_millis(100));
+        assert_eq!(authenticate("one").as_deref(), Some("bob"));
+
+        rewrite(
+            &path,
+            "bob:two\n",
+            SystemTime::now() + Duration::from_secs(10),
+        );
+        wait().await;
+        assert_eq!(authenticate("one"), None);
+        assert_eq!(authenticate("two").as_deref(), Some("bob"));
+
+        // removing the file revokes its tokens, not the configured ones
+        fs::remove_file(&path).unwrap();
+        wait().await;
+        assert_eq!(authenticate("two"), None);
+        assert_eq!(authenticate("secret").as_deref(), Some("alice"));
+    }
+}
@@ -0,0 +1,192 @@
+//
+// Copyright (c) 2026 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use tokio::time::Instant;
+
+use crate::{
+    config::Batching,
+    interface::{self, OutRemoteMessage, SequenceId},
+    outbound::{OutboundMessage, OutboundReceiver},
+};
+
+/// Coalesces the messages queued for a client into `Batch` messages.
+pub(crate) struct Batcher {
+    max_size: usize,
+    max_delay: Duration,
+}
+
+impl Batcher {
+    pub(crate) fn new(config: &Batching) -> Self {
+        Batcher {
+            max_size: config.max_size,
+            max_delay: Duration::from_millis(config.max_delay_ms),
+        }
+    }
+
+    /// Batches the messages queued after a first one until the batch reaches the maximum size,
+    /// waiting up to the maximum delay for more. A single message is not batched.
+    /// `on_message` is given each message added after the first one, and its size.
+    /// Returns the frame with the sequence id in its header: the one of the first message
+    /// if it wasn't batched, none for a `Batch` message.
+    pub(crate) async fn batch(
+        &self,
+        first: Bytes,
+        sequence_id: Option<SequenceId>,
+        rx: &OutboundReceiver,
+        mut on_message: impl FnMut(OutboundMessage, usize),
+    ) -> (Bytes, Option<SequenceId>) {
+        let deadline = Instant::now() + self.max_delay;
+        let mut size = first.len();
+        let mut messages = vec![first];
+        while size < self.max_size {
+            let next = match rx.try_recv() {
+                Some(next) => next,
+                None if self.max_delay.is_zero() => break,
+                None => match tokio::time::timeout_at(deadline, rx.recv()).await {
+                    Ok(next) => next,
+                    Err(_) => break,
+                },
+            };
+            let wire = next.0.to_wire(next.1);
+            size += wire.len();
+            on_message(next, wire.len());
+            messages.push(wire);
+        }
+        if messages.len() == 1 {
+            return (messages.pop().unwrap_or_default(), sequence_id);
+        }
+        tracing::trace!("<< Send: batch of {} messages", messages.len());
+        let batch = OutRemoteMessage::Batch(interface::Batch { messages });
+        (batch.to_wire(None), None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Read,
+        sync::{Arc, Mutex},
+    };
+
+    use flate2::read::DeflateDecoder;
+    use zenoh::bytes::ZBytes;
+    use zenoh_ext::ZDeserializer;
+
+    use super::Batcher;
+    use crate::{
+        commands::command_channel,
+        compression::{Compressor, COMPRESSED_FLAG},
+        config::{Batching, Compression},
+        interface::{self, InRemoteMessageId, OutRemoteMessage, OutRemoteMessageId},
+        outbound::outbound_channel,
+        AdminSpaceClient,
+    };
+
+    fn ok() -> OutRemoteMessage {
+        OutRemoteMessage::Ok(interface::Ok {
+            content_id: InRemoteMessageId::Ping,
+        })
+    }
+
+    fn admin_client() -> AdminSpaceClient {
+        AdminSpaceClient::new(
+            "id".to_string(),
+            "127.0.0.1:10000".to_string(),
+            "10000".to_string(),
+            None,
+            None,
+            command_channel().0,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_batch() {
+        let (tx, rx) = outbound_channel(None, Arc::new(Mutex::new(admin_client())));
+        let batcher = Batcher::new(&Batching {
+            max_size: 10,
+            max_delay_ms: 0,
+        });
+
+        // a single message is sent as is
+        let first = ok().to_wire(Some(1));
+        assert_eq!(
+            batcher.batch(first.clone(), Some(1), &rx, |_, _| {}).await,
+            (first, Some(1))
+        );
+
+        for _ in 0..5 {
+            tx.send((ok(), None)).unwrap();
+        }
+        let mut batched = 0;
+        let (batch, sequence_id) = batcher
+            .batch(ok().to_wire(Some(1)), Some(1), &rx, |_, _| batched += 1)
+            .await;
+        assert_eq!(sequence_id, None);
+        let batch = ZBytes::from(batch.to_vec());
+        let mut deserializer = ZDeserializer::new(&batch);
+        let id: u8 = deserializer.deserialize().unwrap();
+        assert_eq!(id, u8::from(OutRemoteMessageId::Batch));
+        let messages: Vec<Vec<u8>> = deserializer.deserialize().unwrap();
+        assert!(deserializer.done());
+        // stops once the maximum size is reached
+        assert_eq!(messages.len(), 3);
+        assert_eq!(batched, 2);
+        assert_eq!(messages[0], ok().to_wire(Some(1)));
+        assert_eq!(messages[1], ok().to_wire(None));
+        assert!(rx.try_recv().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_batch_compressed() {
+        let (tx, rx) = outbound_channel(None, Arc::new(Mutex::new(admin_client())));
+        let batcher = Batcher::new(&Batching {
+            max_size: 65536,
+            max_delay_ms: 0,
+        });
+        let compressor = Compressor::new(&Compression {
+            min_size: 64,
+            level: 6,
+        });
+        let error = |seq| {
+            let error = "the same error, over and over again".repeat(4);
+            let msg = OutRemoteMessage::Error(interface::Error { error });
+            let wire = msg.to_wire(Some(seq));
+            (msg, wire)
+        };
+        for seq in 2..5 {
+            tx.send((error(seq).0, Some(seq))).unwrap();
+        }
+
+        // the header of a batch has no sequence id, even if its first message has one
+        let (first, sequence_id) = batcher.batch(error(1).1, Some(1), &rx, |_, _| {}).await;
+        let compressed = compressor.compress(first, sequence_id);
+        assert_eq!(
+            compressed[0],
+            u8::from(OutRemoteMessageId::Batch) | COMPRESSED_FLAG
+        );
+        let mut body = Vec::new();
+        DeflateDecoder::new(&compressed[1..])
+            .read_to_end(&mut body)
+            .unwrap();
+        let body = ZBytes::from(body);
+        let mut deserializer = ZDeserializer::new(&body);
+        let messages: Vec<Vec<u8>> = deserializer.deserialize().unwrap();
+        assert!(deserializer.done());
+        let expected: Vec<_> = (1..5).map(|seq| error(seq).1.to_vec()).collect();
+        assert_eq!(messages, expected);
+    }
+}
@@ -0,0 +1,233 @@
+//
+// Copyright (c) 2026 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{fmt, sync::Arc};
+
+use tokio::{
+    select,
+    sync::{mpsc, Notify},
+};
+
+use crate::interface::{
+    InRemoteMessage, LinkEventsListenerId, LivelinessTokenId, MatchingListenerId, PublisherId,
+    QuerierId, QueryableId, SubscriberId, TransportEventsListenerId, UndeclareLinkEventsListener,
+    UndeclareLivelinessSubscriber, UndeclareLivelinessToken, UndeclareMatchingListener,
+    UndeclarePublisher, UndeclareQuerier, UndeclareQueryable, UndeclareSubscriber,
+    UndeclareTransportEventsListener,
+};
+
+/// An entity of a client revoked by the administrator. Its kind is named as in the admin space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RevokedEntity {
+    Publisher(PublisherId),
+    Subscriber(SubscriberId),
+    Queryable(QueryableId),
+    Querier(QuerierId),
+    LivelinessToken(LivelinessTokenId),
+    LivelinessSubscriber(SubscriberId),
+    MatchingListener(MatchingListenerId),
+    TransportEventsListener(TransportEventsListenerId),
+    LinkEventsListener(LinkEventsListenerId),
+}
+
+impl RevokedEntity {
+    fn parse(kind: &str, id: &str) -> Option<Self> {
+        let id: u32 = id.parse().ok()?;
+        let entity = match kind {
+            "publishers" => RevokedEntity::Publisher(PublisherId(id)),
+            "subscribers" => RevokedEntity::Subscriber(SubscriberId(id)),
+            "queryables" => RevokedEntity::Queryable(QueryableId(id)),
+            "queriers" => RevokedEntity::Querier(QuerierId(id)),
+            "liveliness_tokens" => RevokedEntity::LivelinessToken(LivelinessTokenId(id)),
+            "liveliness_subscribers" => RevokedEntity::LivelinessSubscriber(SubscriberId(id)),
+            "matching_listeners" => RevokedEntity::MatchingListener(MatchingListenerId(id)),
+            "transport_events_listeners" => {
+                RevokedEntity::TransportEventsListener(TransportEventsListenerId(id))
+            }
+            "link_events_listeners" => RevokedEntity::LinkEventsListener(LinkEventsListenerId(id)),
+            _ => return None,
+        };
+        Some(entity)
+    }
+
+    /// The message by which the client would have undeclared the entity.
+    pub(crate) fn undeclare(self) -> InRemoteMessage {
+        match self {
+            RevokedEntity::Publisher(id) => {
+                InRemoteMessage::UndeclarePublisher(UndeclarePublisher { id })
+            }
+            RevokedEntity::Subscriber(id) => {
+                InRemoteMessage::UndeclareSubscriber(UndeclareSubscriber { id })
+            }
+            RevokedEntity::Queryable(id) => {
+                InRemoteMessage::UndeclareQueryable(UndeclareQueryable { id })
+            }
+            RevokedEntity::Querier(id) => {
+                InRemoteMessage::UndeclareQuerier(UndeclareQuerier { id })
+            }
+            RevokedEntity::LivelinessToken(id) => {
+                InRemoteMessage::UndeclareLivelinessToken(UndeclareLivelinessToken { id })
+            }
+            RevokedEntity::LivelinessSubscriber(id) => {
+                InRemoteMessage::UndeclareLivelinessSubscriber(UndeclareLivelinessSubscriber { id })
+            }
+            RevokedEntity::MatchingListener(id) => {
+                InRemoteMessage::UndeclareMatchingListener(UndeclareMatchingListener { id })
+            }
+            RevokedEntity::TransportEventsListener(id) => {
+                InRemoteMessage::UndeclareTransportEventsListener(
+                    UndeclareTransportEventsListener { id },
+                )
+            }
+            RevokedEntity::LinkEventsListener(id) => {
+                InRemoteMessage::UndeclareLinkEventsListener(UndeclareLinkEventsListener { id })
+            }
+        }
+    }
+}
+
+impl fmt::Display for RevokedEntity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RevokedEntity::Publisher(id) => write!(f, "Publisher {id}"),
+            RevokedEntity::Subscriber(id) => write!(f, "Subscriber {id}"),
+            RevokedEntity::Queryable(id) => write!(f, "Queryable {id}"),
+            RevokedEntity::Querier(id) => write!(f, "Querier {id}"),
+            RevokedEntity::LivelinessToken(id) => write!(f, "Liveliness token {id}"),
+            RevokedEntity::LivelinessSubscriber(id) => write!(f, "Liveliness subscriber {id}"),
+            RevokedEntity::MatchingListener(id) => write!(f, "Matching listener {id}"),
+            RevokedEntity::TransportEventsListener(id) => {
+                write!(f, "Transport events listener {id}")
+            }
+            RevokedEntity::LinkEventsListener(id) => write!(f, "Link events listener {id}"),
+        }
+    }
+}
+
+/// A command given to a client by deleting its key, or the key of one of its entities,
+/// in the admin space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AdminCommand {
+    /// Closes the connection of the client and ends its session.
+    Disconnect,
+    /// Undeclares an entity of the client.
+    Revoke(RevokedEntity),
+}
+
+impl AdminCommand {
+    /// Parses the segments of a deleted key following `clients/<uuid>`:
+    /// none for the client itself, or the kind and the id of one of its entities.
+    pub(crate) fn parse(segments: &[&str]) -> Option<Self> {
+        match segments {
+            [] => Some(AdminCommand::Disconnect),
+            [kind, id] => RevokedEntity::parse(kind, id).map(AdminCommand::Revoke),
+            _ => None,
+        }
+    }
+}
+
+/// Gives the admin space commands to a client, whether it is connected or its session is suspended.
+#[derive(Debug, Clone)]
+pub(crate) struct CommandSender {
+    disconnect: Arc<Notify>,
+    revocations: mpsc::UnboundedSender<RevokedEntity>,
+}
+
+impl CommandSender {
+    pub(crate) fn send(&self, command: AdminCommand) {
+        match command {
+            AdminCommand::Disconnect => self.disconnect.notify_one(),
+            // the session ended if the receiver was dropped
+            AdminCommand::Revoke(entity) => {
+                let _ = self.revocations.send(entity);
+            }
+        }
+    }
+}
+
+/// The admin space commands of a client. The revocations are queued while its session
+/// is suspended, and carried out when it is resumed.
+pub(crate) struct CommandReceiver {
+    disconnect: Arc<Notify>,
+    revocations: mpsc::UnboundedReceiver<RevokedEntity>,
+}
+
+impl CommandReceiver {
+    pub(crate) async fn recv(&mut self) -> AdminCommand {
+        select! {
+            _ = self.disconnect.notified() => AdminCommand::Disconnect,
+            Some(entity) = self.revocations.recv() => AdminCommand::Revoke(entity),
+        }
+    }
+
+    /// Notified when the client is disconnected, to end its session while it is suspended.
+    pub(crate) fn disconnection(&self) -> Arc<Notify> {
+        self.disconnect.clone()
+    }
+}
+
+pub(crate) fn command_channel() -> (CommandSender, CommandReceiver) {
+    let disconnect = Arc::new(Notify::new());
+    let (tx, rx) = mpsc::unbounded_channel();
+    (
+        CommandSender {
+            disconnect: disconnect.clone(),
+            revocations: tx,
+        },
+        CommandReceiver {
+            disconnect,
+            revocations: rx,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{command_channel, AdminCommand, RevokedEntity};
+    use crate::interface::{PublisherId, SubscriberId};
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(AdminCommand::parse(&[]), Some(AdminCommand::Disconnect));
+        assert_eq!(
+            AdminCommand::parse(&["publishers", "3"]),
+            Some(AdminCommand::Revoke(RevokedEntity::Publisher(PublisherId(
+                3
+            ))))
+        );
+        assert_eq!(
+            AdminCommand::parse(&["liveliness_subscribers", "7"]),
+            Some(AdminCommand::Revoke(RevokedEntity::LivelinessSubscriber(
+                SubscriberId(7)
+            )))
+        );
+        assert_eq!(AdminCommand::parse(&["stats"]), None);
+        assert_eq!(AdminCommand::parse(&["pending_queries", "1"]), None);
+        assert_eq!(AdminCommand::parse(&["subscribers", "-1"]), None);
+        assert_eq!(AdminCommand::parse(&["subscribers", "1", "2"]), None);
+    }
+
+    #[tokio::test]
+    async fn test_command_channel() {
+        let (tx, mut rx) = command_channel();
+        let revoked = AdminCommand::Revoke(RevokedEntity::Subscriber(SubscriberId(1)));
+        tx.send(revoked);
+        assert_eq!(rx.recv().await, revoked);
+
+        // a disconnection requested while suspended is not lost
+        let disconnection = rx.disconnection();
+        tx.send(AdminCommand::Disconnect);
+        disconnection.notified().await;
+    }
+}
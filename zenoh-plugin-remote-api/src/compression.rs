@@ -0,0 +1,102 @@
+//
+// Copyright (c) 2026 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::io::Write;
+
+use bytes::Bytes;
+use flate2::write::DeflateEncoder;
+
+use crate::{config::Compression, interface::SequenceId};
+
+/// Set in the header of the messages sent to the clients whose body is compressed
+/// with raw deflate (RFC 1951). The header and the sequence id are not compressed.
+pub(crate) const COMPRESSED_FLAG: u8 = 0b01000000;
+
+const MAX_LEVEL: u32 = 9;
+
+/// Compresses the body of the messages sent to a client.
+pub(crate) struct Compressor {
+    min_size: usize,
+    level: flate2::Compression,
+}
+
+impl Compressor {
+    pub(crate) fn new(config: &Compression) -> Self {
+        Compressor {
+            min_size: config.min_size,
+            level: flate2::Compression::new(config.level.min(MAX_LEVEL)),
+        }
+    }
+
+    /// Compresses a message serialized by `to_wire`, unless its body is smaller than
+    /// the minimum size or compressing it doesn't make it smaller.
+    pub(crate) fn compress(&self, wire: Bytes, sequence_id: Option<SequenceId>) -> Bytes {
+        let header_len = 1 + sequence_id.map_or(0, |_| std::mem::size_of::<SequenceId>());
+        let body = &wire[header_len..];
+        if body.len() < self.min_size {
+            return wire;
+        }
+        let mut encoder = DeflateEncoder::new(Vec::with_capacity(body.len() / 2), self.level);
+        let compressed = match encoder.write_all(body).and_then(|_| encoder.finish()) {
+            Ok(compressed) if compressed.len() < body.len() => compressed,
+            Ok(_) => return wire,
+            Err(e) => {
+                tracing::warn!("Unable to compress message: {e}");
+                return wire;
+            }
+        };
+        let mut message = Vec::with_capacity(header_len + compressed.len());
+        message.extend_from_slice(&wire[..header_len]);
+        message[0] |= COMPRESSED_FLAG;
+        message.extend_from_slice(&compressed);
+        message.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use bytes::Bytes;
+    use flate2::read::DeflateDecoder;
+
+    use super::{Compressor, COMPRESSED_FLAG};
+    use crate::config::Compression;
+
+    #[test]
+    fn test_compress() {
+        let compressor = Compressor::new(&Compression {
+            min_size: 64,
+            level: 6,
+        });
+
+        // too small
+        let wire = Bytes::from_static(&[0x81, 1, 0, 0, 0, 42]);
+        assert_eq!(compressor.compress(wire.clone(), Some(1)), wire);
+
+        let body = br#"{"temperature": 21.5, "humidity": 40}"#.repeat(10);
+        let mut wire = vec![0x85, 7, 0, 0, 0];
+        wire.extend_from_slice(&body);
+        let compressed = compressor.compress(wire.into(), Some(7));
+        assert_eq!(compressed[0], 0x85 | COMPRESSED_FLAG);
+        assert_eq!(&compressed[1..5], &[7, 0, 0, 0]);
+        assert!(compressed.len() < body.len());
+
+        let mut decompressed = Vec::new();
+        DeflateDecoder::new(&compressed[5..])
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, body);
+    }
+}
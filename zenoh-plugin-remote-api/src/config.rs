@@ -22,6 +22,14 @@ use serde::{
 
 const DEFAULT_HTTP_INTERFACE: &str = "[::]";
 const DEFAULT_WEBSOCKET_PORT: &str = "10000";
+const DEFAULT_TOKEN_QUERY_PARAMETER: &str = "token";
+const REDACTED: &str = "<redacted>";
+const DEFAULT_RESUMPTION_GRACE_PERIOD_SECS: u64 = 30;
+const DEFAULT_COMPRESSION_MIN_SIZE: usize = 1024;
+const DEFAULT_COMPRESSION_LEVEL: u32 = 6;
+const DEFAULT_BATCH_MAX_SIZE: usize = 64 * 1024;
+/// The prefix of the listener addresses which are Unix domain socket paths.
+pub(crate) const UNIX_SOCKET_PREFIX: &str = "unix:";
 
 #[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
@@ -34,6 +42,43 @@ pub struct Config {
 
     pub secure_websocket: Option<SecureWebsocket>,
 
+    /// The WebSocket listeners. When set, `websocket_port` and `secure_websocket` are ignored.
+    #[serde(default)]
+    pub listeners: Vec<Listener>,
+
+    pub http: Option<Http>,
+
+    pub cross_origin: Option<CrossOrigin>,
+
+    pub outbound_queue: Option<OutboundQueue>,
+
+    pub compression: Option<Compression>,
+
+    pub batching: Option<Batching>,
+
+    pub connection_limits: Option<ConnectionLimits>,
+
+    pub message_limits: Option<MessageLimits>,
+
+    pub session_resumption: Option<SessionResumption>,
+
+    /// The oldest version of the protocol accepted from the clients.
+    /// The clients which don't send a `Hello` message speak version 1.
+    /// It can't be newer than the latest version spoken by the plugin.
+    pub min_protocol_version: Option<u32>,
+
+    pub client_liveliness: Option<ClientLiveliness>,
+
+    pub quotas: Option<Quotas>,
+
+    /// The limits on the rate of the publications and queries of each client.
+    #[serde(default)]
+    pub rate_limits: Vec<RateLimit>,
+
+    pub authentication: Option<Authentication>,
+
+    pub access_control: Option<AccessControl>,
+
     #[serde(default, deserialize_with = "deserialize_path")]
     __path__: Option<Vec<String>>,
     __required__: Option<bool>,
@@ -45,6 +90,339 @@ pub struct Config {
 pub struct SecureWebsocket {
     pub certificate_path: String,
     pub private_key_path: String,
+    pub client_auth: Option<ClientAuth>,
+    /// Checks the certificate files every given number of seconds and reloads them if
+    /// they were modified. New connections use the new certificates, existing ones are kept.
+    pub reload_interval_secs: Option<u64>,
+}
+
+/// The plain HTTP requests answered by the listeners, besides `/health` and `/version`.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Http {
+    /// A directory served on the other paths, e.g. the zenoh-ts browser bundle of an application.
+    pub static_directory: Option<String>,
+    /// Exposes the metrics of the plugin on `/metrics`, in the Prometheus text format.
+    pub metrics: Option<Metrics>,
+}
+
+/// The metrics are labelled by listener. The requests for them are checked like the WebSocket
+/// upgrade requests of the listener, against the allowed origins and hosts and, unless the
+/// listener doesn't require it, for a valid token.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Metrics {
+    /// Labels the metrics of the clients by client as well. The series of a client end
+    /// with its session. Since they identify the clients, they are only served to the
+    /// requests presenting a valid token.
+    #[serde(default)]
+    pub per_client: bool,
+}
+
+/// Keeps the state of the clients which opted in to resumption for a grace period after they
+/// disconnect. A client reconnecting in time gets its declarations back, along with the messages
+/// buffered in its outbound queue while it was disconnected.
+///
+/// While the client is disconnected, a message which doesn't fit in its outbound queue and
+/// can't be dropped ends its session. The messages of a frame which failed to be sent are
+/// buffered again, but those written to the connection before it was lost may not have
+/// reached the client.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SessionResumption {
+    #[serde(default = "default_resumption_grace_period_secs")]
+    pub grace_period_secs: u64,
+}
+
+fn default_resumption_grace_period_secs() -> u64 {
+    DEFAULT_RESUMPTION_GRACE_PERIOD_SECS
+}
+
+/// Announces each client to the other zenoh nodes with a liveliness token on `<prefix>/<uuid>`,
+/// declared while its session lasts.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ClientLiveliness {
+    /// The key expression prefixing the tokens, `@/<zid>/remote-plugin/clients` by default.
+    pub prefix: Option<String>,
+    /// Appends `/<app>` to the token of the clients which name their application
+    /// with the `app` query parameter of the upgrade request.
+    #[serde(default)]
+    pub app_name: bool,
+}
+
+/// The maximum number of entities declared by each client and by all the clients together.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Quotas {
+    #[serde(default)]
+    pub per_client: EntityLimits,
+    #[serde(default)]
+    pub global: EntityLimits,
+}
+
+/// The maximum number of entities of each type, unlimited if not set.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct EntityLimits {
+    pub publishers: Option<usize>,
+    pub subscribers: Option<usize>,
+    pub queryables: Option<usize>,
+    pub queriers: Option<usize>,
+    pub liveliness_tokens: Option<usize>,
+    pub liveliness_subscribers: Option<usize>,
+    /// The matching, transport events and link events listeners.
+    pub listeners: Option<usize>,
+}
+
+/// The `Origin` and `Host` headers allowed in the WebSocket upgrade requests, any if not set.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CrossOrigin {
+    /// The origins of the web pages allowed to connect, e.g. `"https://app.example.com"`, or
+    /// `"*"` for any. Requests without an `Origin` header, which browsers always send, are allowed.
+    pub allowed_origins: Option<Vec<String>>,
+    /// The host names the clients connect to, with or without a port, e.g. `"localhost"`.
+    /// Protects local servers from DNS rebinding.
+    pub allowed_hosts: Option<Vec<String>>,
+}
+
+/// Compresses the messages sent to the clients which negotiate the compression feature
+/// in their `Hello` message, from the `HelloAck` message on.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Compression {
+    /// The minimum size of the messages to compress, in bytes.
+    #[serde(default = "default_compression_min_size")]
+    pub min_size: usize,
+    /// The deflate compression level, from 0 to 9.
+    #[serde(default = "default_compression_level")]
+    pub level: u32,
+}
+
+fn default_compression_min_size() -> usize {
+    DEFAULT_COMPRESSION_MIN_SIZE
+}
+
+fn default_compression_level() -> u32 {
+    DEFAULT_COMPRESSION_LEVEL
+}
+
+/// Sends the messages queued for the clients which negotiate the batching feature in their
+/// `Hello` message in `Batch` messages, each in a single WebSocket frame, from the `HelloAck`
+/// message on.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Batching {
+    /// The size from which no more messages are added to a batch, in bytes.
+    #[serde(default = "default_batch_max_size")]
+    pub max_size: usize,
+    /// How long to wait for more messages to add to a batch, in milliseconds.
+    /// By default only the messages already queued are batched.
+    #[serde(default)]
+    pub max_delay_ms: u64,
+}
+
+fn default_batch_max_size() -> usize {
+    DEFAULT_BATCH_MAX_SIZE
+}
+
+/// The limits on the connections of the clients, unlimited if not set.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ConnectionLimits {
+    /// The maximum number of clients, counting the disconnected clients whose session is kept
+    /// for resumption. The connections of the clients exceeding it are closed.
+    pub max_clients: Option<usize>,
+    /// Closes the connections which don't complete the TLS and WebSocket handshakes in time.
+    pub handshake_timeout_secs: Option<u64>,
+    /// Pings the clients from which nothing was received for this duration,
+    /// and closes their connection if they don't answer within the same duration,
+    /// or if sending them a message takes longer.
+    pub idle_timeout_secs: Option<u64>,
+}
+
+/// The maximum sizes of the messages received from the clients, in bytes.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct MessageLimits {
+    /// Closes the connection of a client sending a larger WebSocket frame. Defaults to 16 MiB.
+    pub max_frame_size: Option<usize>,
+    /// Closes the connection of a client sending a larger WebSocket message. Defaults to 64 MiB.
+    pub max_message_size: Option<usize>,
+    /// Rejects the messages with a larger payload. The message is received and deserialized
+    /// before it is rejected: `max_message_size` bounds the memory it uses.
+    pub max_payload_size: Option<usize>,
+    /// Rejects the messages with a larger attachment, checked like `max_payload_size`.
+    pub max_attachment_size: Option<usize>,
+}
+
+/// A token bucket limiting the `Put`, `Delete` and `Get` messages of each client,
+/// refilled every second with the given number of messages and of payload bytes.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimit {
+    pub messages_per_second: Option<u64>,
+    pub bytes_per_second: Option<u64>,
+    /// The key expressions the limit applies to, all of them if empty.
+    #[serde(default)]
+    pub key_exprs: Vec<String>,
+    #[serde(default)]
+    pub on_limit: RateLimitAction,
+}
+
+/// What happens to a message exceeding a rate limit.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitAction {
+    /// Wait for the bucket to refill before reading the next messages of the client.
+    #[default]
+    Backpressure,
+    /// Reject the message with an error.
+    Error,
+}
+
+/// A WebSocket listener, with its own TLS settings and policies.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Listener {
+    /// `<port_number>`, `<local_ip>:<port_number>` or `unix:<path>` for a Unix domain socket.
+    #[serde(deserialize_with = "deserialize_ws_port")]
+    pub bind: String,
+    /// The permissions of the Unix domain socket file in octal, e.g. `"660"`.
+    pub socket_mode: Option<String>,
+    pub secure_websocket: Option<SecureWebsocket>,
+    /// Whether clients must present a valid token. Defaults to `true` if `authentication`
+    /// is configured. Otherwise clients presenting a valid token are still identified.
+    pub auth_required: Option<bool>,
+    /// Only allows clients to subscribe and to query.
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// Verifies the certificates of the clients against a CA bundle.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ClientAuth {
+    pub ca_certificate_path: String,
+    /// Whether clients without a certificate are rejected.
+    #[serde(default = "default_client_auth_required")]
+    pub required: bool,
+}
+
+/// Bounds the number of messages waiting to be sent to a single WebSocket client.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct OutboundQueue {
+    pub capacity: usize,
+    #[serde(default)]
+    pub overflow_policy: OverflowPolicy,
+}
+
+/// What to do when a client's outbound queue is full.
+///
+/// The drop policies only apply to samples published with `CongestionControl::Drop`,
+/// other messages (samples published with `CongestionControl::Block`, queries, replies...)
+/// wait for room in the queue, as they would in zenoh.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// Evict the oldest droppable message from the queue.
+    DropOldest,
+    /// Discard the droppable message being sent.
+    #[default]
+    DropNewest,
+    /// Block the zenoh callback until there is room in the queue.
+    Block,
+    /// Close the connection of the client.
+    Disconnect,
+}
+
+/// Requires WebSocket clients to present a bearer token, either in the `Authorization`
+/// header of the upgrade request or in a query parameter of the URL.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Authentication {
+    #[serde(default)]
+    pub tokens: Vec<UserToken>,
+    /// A file with one `<user>:<token>` entry per line, re-read when it is modified.
+    /// Its tokens are revoked if it is removed.
+    pub tokens_file: Option<String>,
+    #[serde(default = "default_token_query_parameter")]
+    pub query_parameter: String,
+}
+
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct UserToken {
+    pub user: String,
+    /// Redacted when the configuration is serialized, e.g. in the admin space.
+    #[serde(serialize_with = "serialize_redacted")]
+    pub token: String,
+}
+
+/// Restricts the key expressions on which clients may operate.
+///
+/// A request is denied if a matching `deny` rule intersects its key expression,
+/// allowed if a matching `allow` rule includes it, and otherwise gets the default permission.
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AccessControl {
+    pub default_permission: Permission,
+    #[serde(default)]
+    pub rules: Vec<AccessControlRule>,
+}
+
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AccessControlRule {
+    pub permission: Permission,
+    pub actions: Vec<AccessControlAction>,
+    pub key_exprs: Vec<String>,
+    /// The authenticated users the rule applies to.
+    #[serde(default)]
+    pub users: Vec<String>,
+    /// The subjects of the client certificates the rule applies to, e.g. `CN=client1, O=Org`.
+    /// The rule applies to all clients if both `users` and `cert_subjects` are empty.
+    #[serde(default)]
+    pub cert_subjects: Vec<String>,
+}
+
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    Allow,
+    Deny,
+}
+
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessControlAction {
+    /// Declare a publisher, put or delete.
+    Publish,
+    /// Declare a subscriber or a liveliness subscriber.
+    Subscribe,
+    /// Get, get the liveliness tokens or declare a querier.
+    Query,
+    DeclareQueryable,
+    DeclareLivelinessToken,
+}
+
+impl Config {
+    /// Returns the configured listeners, or the one defined by `websocket_port` and `secure_websocket`.
+    pub fn listeners(&self) -> Vec<Listener> {
+        if self.listeners.is_empty() {
+            vec![Listener {
+                bind: self.websocket_port.clone(),
+                socket_mode: None,
+                secure_websocket: self.secure_websocket.clone(),
+                auth_required: None,
+                read_only: false,
+            }]
+        } else {
+            self.listeners.clone()
+        }
+    }
 }
 
 impl From<&Config> for serde_json::Value {
@@ -53,10 +431,22 @@ impl From<&Config> for serde_json::Value {
     }
 }
 
+fn serialize_redacted<T, S: serde::Serializer>(_: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(REDACTED)
+}
+
 fn default_websocket_port() -> String {
     format!("{}:{}", DEFAULT_HTTP_INTERFACE, DEFAULT_WEBSOCKET_PORT)
 }
 
+fn default_client_auth_required() -> bool {
+    true
+}
+
+fn default_token_query_parameter() -> String {
+    DEFAULT_TOKEN_QUERY_PARAMETER.to_string()
+}
+
 fn deserialize_ws_port<'de, D>(deserializer: D) -> Result<String, D::Error>
 where
     D: Deserializer<'de>,
@@ -70,7 +460,7 @@ impl Visitor<'_> for WebsocketVisitor {
     type Value = String;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str(r#"either a port number as an integer or a string, either a string with format "<local_ip>:<port_number>""#)
+        formatter.write_str(r#"either a port number as an integer or a string, either a string with format "<local_ip>:<port_number>" or "unix:<path>""#)
     }
 
     fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
@@ -84,6 +474,12 @@ impl Visitor<'_> for WebsocketVisitor {
     where
         E: de::Error,
     {
+        if let Some(path) = value.strip_prefix(UNIX_SOCKET_PREFIX) {
+            if path.is_empty() {
+                return Err(E::invalid_value(Unexpected::Str(value), &self));
+            }
+            return Ok(value.to_string());
+        }
         let parts: Vec<&str> = value.split(':').collect();
         if parts.len() > 2 {
             return Err(E::invalid_value(Unexpected::Str(value), &self));
@@ -162,7 +558,10 @@ impl<'de> serde::de::Visitor<'de> for PathVisitor {
 
 #[cfg(test)]
 mod tests {
-    use super::{Config, DEFAULT_HTTP_INTERFACE, DEFAULT_WEBSOCKET_PORT};
+    use super::{
+        AccessControlAction, Config, OverflowPolicy, Permission, DEFAULT_HTTP_INTERFACE,
+        DEFAULT_TOKEN_QUERY_PARAMETER, DEFAULT_WEBSOCKET_PORT, REDACTED,
+    };
 
     #[test]
     fn test_path_field() {
@@ -260,4 +659,168 @@ mod tests {
         assert_eq!(__path__, None);
         assert_eq!(__required__, None);
     }
+
+    #[test]
+    fn test_outbound_queue() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(config.outbound_queue.is_none());
+
+        let config =
+            serde_json::from_str::<Config>(r#"{"outbound_queue": {"capacity": 100}}"#).unwrap();
+        let outbound_queue = config.outbound_queue.unwrap();
+        assert_eq!(outbound_queue.capacity, 100);
+        assert_eq!(outbound_queue.overflow_policy, OverflowPolicy::DropNewest);
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"outbound_queue": {"capacity": 100, "overflow_policy": "disconnect"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.outbound_queue.unwrap().overflow_policy,
+            OverflowPolicy::Disconnect
+        );
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"outbound_queue": {"capacity": 100, "overflow_policy": "unknown"}}"#,
+        );
+        assert!(config.is_err());
+    }
+
+    #[test]
+    fn test_authentication() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(config.authentication.is_none());
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"authentication": {"tokens": [{"user": "alice", "token": "secret"}]}}"#,
+        )
+        .unwrap();
+        let authentication = config.authentication.unwrap();
+        assert_eq!(authentication.tokens.len(), 1);
+        assert_eq!(authentication.tokens[0].user, "alice");
+        assert_eq!(authentication.tokens[0].token, "secret");
+        assert_eq!(authentication.tokens_file, None);
+        assert_eq!(
+            authentication.query_parameter,
+            DEFAULT_TOKEN_QUERY_PARAMETER
+        );
+
+        // the tokens are not published in the admin space
+        let value = serde_json::to_value(&authentication).unwrap();
+        assert_eq!(value["tokens"][0]["user"], "alice");
+        assert_eq!(value["tokens"][0]["token"], REDACTED);
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"authentication": {"tokens_file": "/path/to/tokens", "query_parameter": "access_token"}}"#,
+        )
+        .unwrap();
+        let authentication = config.authentication.unwrap();
+        assert!(authentication.tokens.is_empty());
+        assert_eq!(
+            authentication.tokens_file.as_deref(),
+            Some("/path/to/tokens")
+        );
+        assert_eq!(authentication.query_parameter, "access_token");
+    }
+
+    #[test]
+    fn test_access_control() {
+        let config = serde_json::from_str::<Config>(
+            r#"{"access_control": {"default_permission": "deny", "rules": [
+                {"permission": "allow", "actions": ["publish", "subscribe"], "key_exprs": ["demo/**"], "users": ["alice"]},
+                {"permission": "deny", "actions": ["declare_liveliness_token"], "key_exprs": ["@/**"]}
+            ]}}"#,
+        )
+        .unwrap();
+        let access_control = config.access_control.unwrap();
+        assert_eq!(access_control.default_permission, Permission::Deny);
+        assert_eq!(access_control.rules.len(), 2);
+        assert_eq!(
+            access_control.rules[0].actions,
+            vec![AccessControlAction::Publish, AccessControlAction::Subscribe]
+        );
+        assert_eq!(access_control.rules[0].users, vec!["alice".to_string()]);
+        assert!(access_control.rules[1].users.is_empty());
+        assert!(access_control.rules[1].cert_subjects.is_empty());
+
+        let config = serde_json::from_str::<Config>(r#"{"access_control": {"rules": []}}"#);
+        assert!(config.is_err());
+    }
+
+    #[test]
+    fn test_client_auth() {
+        let config = serde_json::from_str::<Config>(
+            r#"{"secure_websocket": {"certificate_path": "cert.pem", "private_key_path": "key.pem"}}"#,
+        )
+        .unwrap();
+        assert!(config.secure_websocket.unwrap().client_auth.is_none());
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"secure_websocket": {"certificate_path": "cert.pem", "private_key_path": "key.pem",
+                "client_auth": {"ca_certificate_path": "ca.pem"}}}"#,
+        )
+        .unwrap();
+        let client_auth = config.secure_websocket.unwrap().client_auth.unwrap();
+        assert_eq!(client_auth.ca_certificate_path, "ca.pem");
+        assert!(client_auth.required);
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"secure_websocket": {"certificate_path": "cert.pem", "private_key_path": "key.pem",
+                "client_auth": {"ca_certificate_path": "ca.pem", "required": false}}}"#,
+        )
+        .unwrap();
+        let secure_websocket = config.secure_websocket.unwrap();
+        assert!(!secure_websocket.client_auth.unwrap().required);
+        assert_eq!(secure_websocket.reload_interval_secs, None);
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"secure_websocket": {"certificate_path": "cert.pem", "private_key_path": "key.pem",
+                "reload_interval_secs": 3600}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.secure_websocket.unwrap().reload_interval_secs,
+            Some(3600)
+        );
+    }
+
+    #[test]
+    fn test_listeners() {
+        let config = serde_json::from_str::<Config>(r#"{"websocket_port": 8080}"#).unwrap();
+        let listeners = config.listeners();
+        assert_eq!(listeners.len(), 1);
+        assert_eq!(listeners[0].bind, format!("{DEFAULT_HTTP_INTERFACE}:8080"));
+        assert_eq!(listeners[0].auth_required, None);
+        assert!(!listeners[0].read_only);
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"listeners": [
+                {"bind": "127.0.0.1:8080", "auth_required": false, "read_only": true},
+                {"bind": "8443", "secure_websocket": {"certificate_path": "cert.pem", "private_key_path": "key.pem"}}
+            ]}"#,
+        )
+        .unwrap();
+        let listeners = config.listeners();
+        assert_eq!(listeners.len(), 2);
+        assert_eq!(listeners[0].bind, "127.0.0.1:8080");
+        assert_eq!(listeners[0].auth_required, Some(false));
+        assert!(listeners[0].read_only);
+        assert!(listeners[0].secure_websocket.is_none());
+        assert_eq!(listeners[1].bind, format!("{DEFAULT_HTTP_INTERFACE}:8443"));
+        assert!(listeners[1].secure_websocket.is_some());
+
+        let config = serde_json::from_str::<Config>(r#"{"listeners": [{"read_only": true}]}"#);
+        assert!(config.is_err());
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"listeners": [{"bind": "unix:/run/zenoh/remote-api.sock", "socket_mode": "660"}]}"#,
+        )
+        .unwrap();
+        let listeners = config.listeners();
+        assert_eq!(listeners[0].bind, "unix:/run/zenoh/remote-api.sock");
+        assert_eq!(listeners[0].socket_mode.as_deref(), Some("660"));
+
+        let config = serde_json::from_str::<Config>(r#"{"listeners": [{"bind": "unix:"}]}"#);
+        assert!(config.is_err());
+    }
 }
@@ -0,0 +1,180 @@
+//
+// Copyright (c) 2026 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+/// The number of clients, connected or whose session is kept for resumption.
+#[derive(Default)]
+pub(crate) struct ClientCount(Arc<AtomicUsize>);
+
+impl ClientCount {
+    /// Counts a new client, `None` if there are already `max` clients.
+    pub(crate) fn acquire(&self, max: Option<usize>) -> Option<ClientSlot> {
+        let max = max.unwrap_or(usize::MAX);
+        self.0
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < max).then_some(n + 1)
+            })
+            .ok()?;
+        Some(ClientSlot(self.0.clone()))
+    }
+}
+
+/// A client counted in the maximum number of clients, until dropped.
+pub(crate) struct ClientSlot(Arc<AtomicUsize>);
+
+impl Drop for ClientSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum KeepaliveEvent {
+    /// Nothing to do until then.
+    Wait(Instant),
+    /// The client was idle for the timeout and must be pinged.
+    Ping,
+    /// The client didn't answer the ping within the timeout.
+    TimedOut,
+}
+
+struct KeepaliveState {
+    last_seen: Instant,
+    pinged_at: Option<Instant>,
+}
+
+/// Detects the clients which stopped answering, by pinging them once they are idle.
+pub(crate) struct Keepalive {
+    timeout: Option<Duration>,
+    state: Mutex<KeepaliveState>,
+}
+
+impl Keepalive {
+    pub(crate) fn new(timeout: Option<Duration>) -> Self {
+        Keepalive {
+            timeout,
+            state: Mutex::new(KeepaliveState {
+                last_seen: Instant::now(),
+                pinged_at: None,
+            }),
+        }
+    }
+
+    /// Records that something was received from the client.
+    pub(crate) fn seen(&self) {
+        self.state.lock().unwrap().last_seen = Instant::now();
+    }
+
+    fn poll(&self, timeout: Duration, now: Instant) -> KeepaliveEvent {
+        let mut state = self.state.lock().unwrap();
+        match state
+            .pinged_at
+            .filter(|pinged_at| *pinged_at >= state.last_seen)
+        {
+            Some(pinged_at) if now >= pinged_at + timeout => KeepaliveEvent::TimedOut,
+            Some(pinged_at) => KeepaliveEvent::Wait(pinged_at + timeout),
+            None if now >= state.last_seen + timeout => {
+                state.pinged_at = Some(now);
+                KeepaliveEvent::Ping
+            }
+            None => KeepaliveEvent::Wait(state.last_seen + timeout),
+        }
+    }
+
+    /// Waits until the client must be pinged or timed out, forever if there is no timeout.
+    pub(crate) async fn next(&self) -> KeepaliveEvent {
+        let Some(timeout) = self.timeout else {
+            return std::future::pending().await;
+        };
+        loop {
+            match self.poll(timeout, Instant::now()) {
+                KeepaliveEvent::Wait(deadline) => tokio::time::sleep_until(deadline).await,
+                event => return event,
+            }
+        }
+    }
+
+    /// Bounds a send to the client by the timeout, `None` if it didn't complete in time:
+    /// a client which stopped reading its connection is as idle as one which stopped writing.
+    pub(crate) async fn bounded<F: Future>(&self, send: F) -> Option<F::Output> {
+        match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, send).await.ok(),
+            None => Some(send.await),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{ClientCount, Keepalive, KeepaliveEvent};
+
+    #[test]
+    fn test_client_count() {
+        let clients = ClientCount::default();
+        let first = clients.acquire(Some(2)).unwrap();
+        let _second = clients.acquire(Some(2)).unwrap();
+        assert!(clients.acquire(Some(2)).is_none());
+        drop(first);
+        assert!(clients.acquire(Some(2)).is_some());
+        assert!(clients.acquire(None).is_some());
+    }
+
+    #[test]
+    fn test_keepalive() {
+        let timeout = Duration::from_secs(10);
+        let keepalive = Keepalive::new(Some(timeout));
+        let start = keepalive.state.lock().unwrap().last_seen;
+        let secs = |s| start + Duration::from_secs(s);
+
+        assert_eq!(
+            keepalive.poll(timeout, secs(5)),
+            KeepaliveEvent::Wait(secs(10))
+        );
+        assert_eq!(keepalive.poll(timeout, secs(10)), KeepaliveEvent::Ping);
+        assert_eq!(
+            keepalive.poll(timeout, secs(15)),
+            KeepaliveEvent::Wait(secs(20))
+        );
+        assert_eq!(keepalive.poll(timeout, secs(20)), KeepaliveEvent::TimedOut);
+
+        // the client answering the ping is pinged again once idle
+        keepalive.state.lock().unwrap().last_seen = secs(12);
+        assert_eq!(
+            keepalive.poll(timeout, secs(20)),
+            KeepaliveEvent::Wait(secs(22))
+        );
+        assert_eq!(keepalive.poll(timeout, secs(22)), KeepaliveEvent::Ping);
+    }
+
+    #[tokio::test]
+    async fn test_keepalive_bounded() {
+        let keepalive = Keepalive::new(Some(Duration::from_millis(10)));
+        assert_eq!(keepalive.bounded(async { 1 }).await, Some(1));
+        assert_eq!(keepalive.bounded(std::future::pending::<()>()).await, None);
+        let keepalive = Keepalive::new(None);
+        assert_eq!(keepalive.bounded(async { 1 }).await, Some(1));
+    }
+}
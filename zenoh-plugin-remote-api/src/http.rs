@@ -0,0 +1,536 @@
+//
+// Copyright (c) 2026 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+use tokio_tungstenite::tungstenite::{
+    handshake::server::{ErrorResponse, Request},
+    http::StatusCode,
+};
+use zenoh_plugin_trait::Plugin;
+
+use crate::{
+    config::Http,
+    metrics::{self, ServerMetrics},
+    RemoteApiPlugin,
+};
+
+/// Large enough for the cookies browsers send along with the upgrade requests.
+const MAX_HEAD_SIZE: usize = 64 * 1024;
+/// As many headers as tungstenite accepts in the upgrade requests.
+const MAX_HEADERS: usize = 124;
+
+/// A stream on which the bytes already read from it are read again first.
+pub struct PrefixedStream<S> {
+    prefix: Vec<u8>,
+    read: usize,
+    inner: S,
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for PrefixedStream<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        if self.read < self.prefix.len() {
+            let len = buf.remaining().min(self.prefix.len() - self.read);
+            buf.put_slice(&self.prefix[self.read..self.read + len]);
+            self.read += len;
+            return Poll::Ready(Ok(()));
+        }
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for PrefixedStream<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct RequestHead {
+    method: String,
+    path: String,
+    headers: Vec<(String, Vec<u8>)>,
+    upgrade: bool,
+}
+
+impl RequestHead {
+    /// The request, to be checked like the WebSocket upgrade requests.
+    fn request(&self) -> Option<Request> {
+        let mut builder = Request::builder()
+            .method(self.method.as_str())
+            .uri(self.path.as_str());
+        for (name, value) in &self.headers {
+            builder = builder.header(name.as_str(), value.as_slice());
+        }
+        builder.body(()).ok()
+    }
+}
+
+/// Parses the head of an HTTP request, returns `None` if it is not complete yet.
+fn parse_head(buf: &[u8]) -> io::Result<Option<RequestHead>> {
+    let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+    let mut request = httparse::Request::new(&mut headers);
+    match request.parse(buf) {
+        Ok(httparse::Status::Complete(_)) => {}
+        Ok(httparse::Status::Partial) => return Ok(None),
+        Err(e) => return Err(io::Error::new(ErrorKind::InvalidData, e)),
+    }
+    let upgrade = request.headers.iter().any(|h| {
+        h.name.eq_ignore_ascii_case("upgrade")
+            && String::from_utf8_lossy(h.value)
+                .to_ascii_lowercase()
+                .contains("websocket")
+    });
+    Ok(Some(RequestHead {
+        method: request.method.unwrap_or_default().to_string(),
+        path: request.path.unwrap_or_default().to_string(),
+        headers: request
+            .headers
+            .iter()
+            .map(|h| (h.name.to_string(), h.value.to_vec()))
+            .collect(),
+        upgrade,
+    }))
+}
+
+/// Returns the file of the static directory a request path points to,
+/// `None` if it doesn't exist or if the path tries to escape the directory.
+fn resolve_path(directory: &Path, path: &str) -> Option<PathBuf> {
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let mut file = directory.to_path_buf();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => return None,
+            s if s.contains('\\') => return None,
+            s => file.push(s),
+        }
+    }
+    if file.is_dir() {
+        file.push("index.html");
+    }
+    // a symbolic link must not lead out of the directory either
+    let file = file.canonicalize().ok()?;
+    file.starts_with(directory.canonicalize().ok()?)
+        .then_some(file)
+}
+
+fn content_type(file: &Path) -> &'static str {
+    match file.extension().and_then(|e| e.to_str()) {
+        Some("html" | "htm") => "text/html; charset=utf-8",
+        Some("js" | "mjs") => "text/javascript; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("json" | "map") => "application/json",
+        Some("wasm") => "application/wasm",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("ico") => "image/x-icon",
+        Some("txt") => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+fn response(status: StatusCode, headers: &[(&str, &str)], body: &[u8], head_only: bool) -> Vec<u8> {
+    let mut response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        status.as_u16(),
+        status.canonical_reason().unwrap_or_default(),
+        body.len()
+    );
+    for (name, value) in headers {
+        response.push_str(&format!("{name}: {value}\r\n"));
+    }
+    response.push_str("\r\n");
+    let mut response = response.into_bytes();
+    if !head_only {
+        response.extend_from_slice(body);
+    }
+    response
+}
+
+/// The response rejecting a request, as for a WebSocket upgrade request.
+fn rejected(error: ErrorResponse, head_only: bool) -> Vec<u8> {
+    let headers = error
+        .headers()
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.to_str().unwrap_or_default()))
+        .collect::<Vec<_>>();
+    let body = error.body().as_deref().unwrap_or_default();
+    response(error.status(), &headers, body.as_bytes(), head_only)
+}
+
+fn not_found(head_only: bool) -> Vec<u8> {
+    response(
+        StatusCode::NOT_FOUND,
+        &[("Content-Type", "text/plain; charset=utf-8")],
+        b"Not Found",
+        head_only,
+    )
+}
+
+async fn respond(
+    head: &RequestHead,
+    http: Option<&Http>,
+    metrics: &ServerMetrics,
+    check: impl FnOnce(&Request) -> Result<Option<String>, ErrorResponse>,
+) -> Vec<u8> {
+    let head_only = match head.method.as_str() {
+        "GET" => false,
+        "HEAD" => true,
+        _ => {
+            return response(
+                StatusCode::METHOD_NOT_ALLOWED,
+                &[("Allow", "GET, HEAD")],
+                &[],
+                false,
+            )
+        }
+    };
+    let json = [("Content-Type", "application/json")];
+    let metrics_config = http.and_then(|http| http.metrics.as_ref());
+    match head.path.split(['?', '#']).next().unwrap_or_default() {
+        "/health" => response(StatusCode::OK, &json, br#"{"status":"ok"}"#, head_only),
+        "/version" => {
+            let body = serde_json::json!({
+                "version": RemoteApiPlugin::PLUGIN_VERSION,
+                "long_version": RemoteApiPlugin::PLUGIN_LONG_VERSION,
+            });
+            response(
+                StatusCode::OK,
+                &json,
+                body.to_string().as_bytes(),
+                head_only,
+            )
+        }
+        "/metrics" if metrics_config.is_some() => {
+            let Some(request) = head.request() else {
+                return response(StatusCode::BAD_REQUEST, &[], &[], head_only);
+            };
+            let user = match check(&request) {
+                Ok(user) => user,
+                Err(error) => return rejected(error, head_only),
+            };
+            // the series of the clients identify them
+            let per_client =
+                user.is_some() && metrics_config.is_some_and(|config| config.per_client);
+            let body = metrics.export(per_client).await;
+            response(
+                StatusCode::OK,
+                &[("Content-Type", metrics::CONTENT_TYPE)],
+                body.as_bytes(),
+                head_only,
+            )
+        }
+        path => {
+            let static_directory = http.and_then(|http| http.static_directory.as_deref());
+            let Some(file) = static_directory.and_then(|d| resolve_path(Path::new(d), path)) else {
+                return not_found(head_only);
+            };
+            match tokio::fs::read(&file).await {
+                Ok(body) => response(
+                    StatusCode::OK,
+                    &[("Content-Type", content_type(&file))],
+                    &body,
+                    head_only,
+                ),
+                Err(e) => {
+                    tracing::debug!("Unable to serve {file:?}: {e}");
+                    not_found(head_only)
+                }
+            }
+        }
+    }
+}
+
+/// Reads the head of the HTTP request opening a connection. WebSocket upgrade requests are
+/// returned the stream to carry on the handshake with, on which the head is read again.
+/// Plain HTTP requests are answered and their connection closed. The requests for the metrics
+/// are checked like the upgrade requests by `check`, which returns the authenticated user.
+pub(crate) async fn accept<S>(
+    mut stream: S,
+    http: Option<&Http>,
+    metrics: &ServerMetrics,
+    check: impl FnOnce(&Request) -> Result<Option<String>, ErrorResponse>,
+) -> io::Result<Option<PrefixedStream<S>>>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    let head = loop {
+        let len = stream.read(&mut chunk).await?;
+        if len == 0 {
+            return Err(io::Error::new(
+                ErrorKind::UnexpectedEof,
+                "connection closed before the end of the request head",
+            ));
+        }
+        buf.extend_from_slice(&chunk[..len]);
+        if let Some(head) = parse_head(&buf)? {
+            break head;
+        }
+        if buf.len() > MAX_HEAD_SIZE {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "request head too large",
+            ));
+        }
+    };
+    if head.upgrade {
+        return Ok(Some(PrefixedStream {
+            prefix: buf,
+            read: 0,
+            inner: stream,
+        }));
+    }
+    tracing::debug!("HTTP {} {}", head.method, head.path);
+    stream
+        .write_all(&respond(&head, http, metrics, check).await)
+        .await?;
+    stream.shutdown().await?;
+    Ok(None)
+}
+
+#[cfg(test)]
+// the error response type is imposed by tungstenite
+#[allow(clippy::result_large_err)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use std::sync::{Arc, Mutex};
+
+    use tokio_tungstenite::tungstenite::http::header;
+
+    use super::{accept, parse_head, resolve_path, MAX_HEADERS};
+    use crate::{
+        auth,
+        commands::command_channel,
+        config::{Http, Metrics},
+        interface::InRemoteMessageId,
+        metrics::ServerMetrics,
+        AdminSpaceClient, StateMap,
+    };
+
+    #[test]
+    fn test_parse_head() {
+        let head = parse_head(b"GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap()
+            .unwrap();
+        assert_eq!(head.method, "GET");
+        assert_eq!(head.path, "/health");
+        assert!(!head.upgrade);
+
+        let head = parse_head(b"GET / HTTP/1.1\r\nUpgrade: WebSocket\r\n\r\n")
+            .unwrap()
+            .unwrap();
+        assert!(head.upgrade);
+
+        assert!(parse_head(b"GET / HTTP/1.1\r\nHost:").unwrap().is_none());
+        assert!(parse_head(b"\x16\x03\x01").is_err());
+
+        // the heads of the browsers, with many headers and large cookies
+        let mut head = "GET / HTTP/1.1\r\nUpgrade: websocket\r\n".to_string();
+        for n in 2..MAX_HEADERS {
+            head.push_str(&format!("X-Header-{n}: {n}\r\n"));
+        }
+        let cookie = format!("Cookie: session={}\r\n\r\n", "a".repeat(16 * 1024));
+        let head = parse_head(format!("{head}{cookie}").as_bytes())
+            .unwrap()
+            .unwrap();
+        assert_eq!(head.headers.len(), MAX_HEADERS);
+        assert!(head.upgrade);
+    }
+
+    #[test]
+    fn test_resolve_path() {
+        let root = std::env::temp_dir().join(format!("remote-api-{}", uuid::Uuid::new_v4()));
+        let dir = root.join("app");
+        std::fs::create_dir_all(dir.join("js")).unwrap();
+        std::fs::write(dir.join("index.html"), "").unwrap();
+        std::fs::write(dir.join("js/app.js"), "").unwrap();
+        std::fs::write(root.join("secret"), "").unwrap();
+        let canonical = |path: &str| Some(dir.join(path).canonicalize().unwrap());
+
+        assert_eq!(resolve_path(&dir, "/js/app.js?v=1"), canonical("js/app.js"));
+        assert_eq!(resolve_path(&dir, "/./index.html"), canonical("index.html"));
+        assert_eq!(resolve_path(&dir, "/"), canonical("index.html"));
+        assert_eq!(resolve_path(&dir, "/missing.html"), None);
+        assert_eq!(resolve_path(&dir, "/../secret"), None);
+        assert_eq!(resolve_path(&dir, "/js/..%2f/../x"), None);
+        assert_eq!(resolve_path(&dir, "/..\\x"), None);
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(root.join("secret"), dir.join("link")).unwrap();
+            std::os::unix::fs::symlink(&root, dir.join("up")).unwrap();
+            assert_eq!(resolve_path(&dir, "/link"), None);
+            assert_eq!(resolve_path(&dir, "/up/secret"), None);
+        }
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_accept() {
+        let metrics = ServerMetrics::new(Default::default());
+        let (mut client, server) = tokio::io::duplex(4096);
+        client
+            .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        assert!(accept(server, None, &metrics, |_| Ok(None))
+            .await
+            .unwrap()
+            .is_none());
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(r#"{"status":"ok"}"#));
+
+        let (mut client, server) = tokio::io::duplex(4096);
+        client
+            .write_all(b"GET /app.js HTTP/1.1\r\n\r\n")
+            .await
+            .unwrap();
+        assert!(accept(server, None, &metrics, |_| Ok(None))
+            .await
+            .unwrap()
+            .is_none());
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+        // the metrics are exposed when enabled
+        let http = Http {
+            static_directory: None,
+            metrics: Some(Metrics { per_client: false }),
+        };
+        for (http, status) in [(None, "404 Not Found"), (Some(&http), "200 OK")] {
+            let (mut client, server) = tokio::io::duplex(4096);
+            client
+                .write_all(b"GET /metrics HTTP/1.1\r\n\r\n")
+                .await
+                .unwrap();
+            assert!(accept(server, http, &metrics, |_| Ok(None))
+                .await
+                .unwrap()
+                .is_none());
+            let mut response = String::new();
+            client.read_to_string(&mut response).await.unwrap();
+            assert!(response.starts_with(&format!("HTTP/1.1 {status}\r\n")));
+        }
+
+        let request = b"GET / HTTP/1.1\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\r\n";
+        let (mut client, server) = tokio::io::duplex(4096);
+        client.write_all(request).await.unwrap();
+        let mut stream = accept(server, None, &metrics, |_| Ok(None))
+            .await
+            .unwrap()
+            .unwrap();
+        let mut replayed = vec![0u8; request.len()];
+        stream.read_exact(&mut replayed).await.unwrap();
+        assert_eq!(replayed, request);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_access() {
+        let state_map = StateMap::default();
+        let mut client = AdminSpaceClient::new(
+            "a".to_string(),
+            "127.0.0.1:50000".to_string(),
+            "[::]:10000".to_string(),
+            None,
+            None,
+            command_channel().0,
+        );
+        client.register_received(InRemoteMessageId::Put, 10);
+        state_map
+            .write()
+            .await
+            .insert("a".to_string(), Arc::new(Mutex::new(client)));
+        let metrics = ServerMetrics::new(state_map);
+        let http = Http {
+            static_directory: None,
+            metrics: Some(Metrics { per_client: true }),
+        };
+        // a listener requiring the token `secret`
+        let check = |request: &super::Request| match request.headers().get(header::AUTHORIZATION) {
+            Some(value) if value == "Bearer secret" => Ok(Some("admin".to_string())),
+            _ => Err(auth::unauthorized("Invalid or missing token")),
+        };
+
+        let get = |request: &'static [u8]| {
+            let http = &http;
+            let metrics = &metrics;
+            async move {
+                let (mut client, server) = tokio::io::duplex(4096);
+                client.write_all(request).await.unwrap();
+                assert!(accept(server, Some(http), metrics, check)
+                    .await
+                    .unwrap()
+                    .is_none());
+                let mut response = String::new();
+                client.read_to_string(&mut response).await.unwrap();
+                response
+            }
+        };
+
+        let response = get(b"GET /metrics HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 401 Unauthorized\r\n"));
+        assert!(response.contains("www-authenticate: Bearer\r\n"));
+        assert!(!response.contains("zenoh_remote_api_"));
+
+        let response = get(b"GET /metrics HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("client=\"a\""));
+
+        // the clients are not identified to unauthenticated requests
+        let (mut client, server) = tokio::io::duplex(4096);
+        client
+            .write_all(b"GET /metrics HTTP/1.1\r\n\r\n")
+            .await
+            .unwrap();
+        assert!(accept(server, Some(&http), &metrics, |_| Ok(None))
+            .await
+            .unwrap()
+            .is_none());
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("zenoh_remote_api_clients{listener=\"[::]:10000\"} 1\n"));
+        assert!(!response.contains("client=\"a\""));
+    }
+}
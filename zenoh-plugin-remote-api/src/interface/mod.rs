@@ -12,7 +12,7 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
-use std::ops::Not;
+use std::{ops::Not, time::Duration};
 
 use uhlc::{Timestamp, NTP64};
 use zenoh::{
@@ -24,7 +24,9 @@ use zenoh::{
     sample::{Locality, SampleKind},
 };
 use zenoh_ext::{Deserialize, Serialize, ZDeserializeError, ZDeserializer, ZSerializer};
-use zenoh_result::bail;
+use zenoh_result::{bail, zerror};
+
+use crate::{config::MessageLimits, protocol::LEGACY_PROTOCOL_VERSION};
 
 pub(crate) type SequenceId = u32;
 
@@ -353,6 +355,60 @@ impl PingAck {
     }
 }
 
+/// Sent by a client right after connecting, with the range of versions of the protocol
+/// it supports and the bits of the features it supports.
+pub(crate) struct Hello {
+    pub(crate) version: u32,
+    pub(crate) min_version: u32,
+    pub(crate) features: u32,
+}
+
+impl Hello {
+    pub(crate) fn from_wire(deserializer: &mut ZDeserializer) -> Result<Self, zenoh_result::Error> {
+        Ok(Hello {
+            version: deserializer.deserialize()?,
+            min_version: deserializer.deserialize()?,
+            features: deserializer.deserialize()?,
+        })
+    }
+}
+
+/// The version of the protocol spoken on the connection, and the features enabled on it.
+pub(crate) struct HelloAck {
+    pub(crate) version: u32,
+    pub(crate) features: u32,
+}
+
+impl HelloAck {
+    pub(crate) fn to_wire(&self, serializer: &mut ZSerializer) {
+        serializer.serialize(self.version);
+        serializer.serialize(self.features);
+    }
+}
+
+pub(crate) struct ResumeToken {
+    pub(crate) token: String,
+    pub(crate) resumed: bool,
+}
+
+impl ResumeToken {
+    pub(crate) fn to_wire(&self, serializer: &mut ZSerializer) {
+        serializer.serialize(&self.token);
+        serializer.serialize(self.resumed);
+    }
+}
+
+/// Messages sent to a client in a single WebSocket frame, each one serialized by `to_wire`.
+pub(crate) struct Batch {
+    pub(crate) messages: Vec<bytes::Bytes>,
+}
+
+impl Batch {
+    pub(crate) fn to_wire(&self, serializer: &mut ZSerializer) {
+        serializer.serialize_iter(self.messages.iter().map(|m| m.as_ref()));
+    }
+}
+
 pub(crate) struct DeclarePublisher {
     pub(crate) id: PublisherId,
     pub(crate) keyexpr: OwnedKeyExpr,
@@ -371,6 +427,58 @@ impl DeclarePublisher {
     }
 }
 
+/// The heartbeat by which an advanced publisher lets the subscribers detect missed samples.
+pub(crate) enum Heartbeat {
+    /// Misses are only detected on the reception of the next sample.
+    None,
+    /// Sent periodically.
+    Periodic(Duration),
+    /// Sent periodically, only if samples were published since the last one.
+    Sporadic(Duration),
+}
+
+impl Deserialize for Heartbeat {
+    fn deserialize(deserializer: &mut ZDeserializer) -> Result<Self, ZDeserializeError> {
+        let mode: u8 = deserializer.deserialize()?;
+        let period = Duration::from_millis(deserializer.deserialize()?);
+        match mode {
+            0 => Ok(Heartbeat::None),
+            1 if !period.is_zero() => Ok(Heartbeat::Periodic(period)),
+            2 if !period.is_zero() => Ok(Heartbeat::Sporadic(period)),
+            _ => Err(ZDeserializeError),
+        }
+    }
+}
+
+pub(crate) struct DeclareAdvancedPublisher {
+    pub(crate) id: PublisherId,
+    pub(crate) keyexpr: OwnedKeyExpr,
+    pub(crate) encoding: Encoding,
+    pub(crate) qos: Qos,
+    /// The number of samples kept per key for the late joiners and the recovery of misses.
+    pub(crate) cache_max_samples: Option<u32>,
+    pub(crate) sample_miss_detection: Option<Heartbeat>,
+    pub(crate) publisher_detection: bool,
+    pub(crate) publisher_detection_metadata: Option<OwnedKeyExpr>,
+}
+
+impl DeclareAdvancedPublisher {
+    pub(crate) fn from_wire(deserializer: &mut ZDeserializer) -> Result<Self, zenoh_result::Error> {
+        Ok(DeclareAdvancedPublisher {
+            id: deserializer.deserialize()?,
+            keyexpr: OwnedKeyExpr::try_from(deserializer.deserialize::<String>()?)?,
+            encoding: encoding_from_id_schema(deserializer.deserialize()?),
+            qos: deserializer.deserialize()?,
+            cache_max_samples: deserialize_option(deserializer)?,
+            sample_miss_detection: deserialize_option(deserializer)?,
+            publisher_detection: deserializer.deserialize()?,
+            publisher_detection_metadata: deserialize_option::<String>(deserializer)?
+                .map(OwnedKeyExpr::try_from)
+                .transpose()?,
+        })
+    }
+}
+
 pub(crate) struct UndeclarePublisher {
     pub(crate) id: PublisherId,
 }
@@ -437,6 +545,68 @@ impl DeclareSubscriber {
     }
 }
 
+/// The samples published before the declaration of an advanced subscriber, which it retrieves.
+pub(crate) struct History {
+    /// Also retrieves the history of the publishers appearing later.
+    pub(crate) detect_late_publishers: bool,
+    pub(crate) max_samples: Option<u32>,
+    pub(crate) max_age_ms: Option<u64>,
+}
+
+impl Deserialize for History {
+    fn deserialize(deserializer: &mut ZDeserializer) -> Result<Self, ZDeserializeError> {
+        Ok(History {
+            detect_late_publishers: deserializer.deserialize()?,
+            max_samples: deserialize_option(deserializer)?,
+            max_age_ms: deserialize_option(deserializer)?,
+        })
+    }
+}
+
+/// How an advanced subscriber retrieves the samples it missed.
+pub(crate) enum Recovery {
+    /// When notified by the heartbeat of the publishers.
+    Heartbeat,
+    /// By querying the publishers periodically.
+    PeriodicQueries(Duration),
+}
+
+impl Deserialize for Recovery {
+    fn deserialize(deserializer: &mut ZDeserializer) -> Result<Self, ZDeserializeError> {
+        let mode: u8 = deserializer.deserialize()?;
+        let period = Duration::from_millis(deserializer.deserialize()?);
+        match mode {
+            0 => Ok(Recovery::Heartbeat),
+            1 if !period.is_zero() => Ok(Recovery::PeriodicQueries(period)),
+            _ => Err(ZDeserializeError),
+        }
+    }
+}
+
+pub(crate) struct DeclareAdvancedSubscriber {
+    pub(crate) id: SubscriberId,
+    pub(crate) keyexpr: OwnedKeyExpr,
+    pub(crate) allowed_origin: Locality,
+    pub(crate) history: Option<History>,
+    pub(crate) recovery: Option<Recovery>,
+    pub(crate) query_timeout_ms: Option<u64>,
+    pub(crate) subscriber_detection: bool,
+}
+
+impl DeclareAdvancedSubscriber {
+    pub(crate) fn from_wire(deserializer: &mut ZDeserializer) -> Result<Self, zenoh_result::Error> {
+        Ok(DeclareAdvancedSubscriber {
+            id: deserializer.deserialize()?,
+            keyexpr: OwnedKeyExpr::try_from(deserializer.deserialize::<String>()?)?,
+            allowed_origin: locality_from_u8(deserializer.deserialize()?)?,
+            history: deserialize_option(deserializer)?,
+            recovery: deserialize_option(deserializer)?,
+            query_timeout_ms: deserialize_option(deserializer)?,
+            subscriber_detection: deserializer.deserialize()?,
+        })
+    }
+}
+
 pub(crate) struct UndeclareSubscriber {
     pub(crate) id: SubscriberId,
 }
@@ -934,6 +1104,23 @@ impl Sample {
     }
 }
 
+/// Samples of a publisher which an advanced subscriber missed and could not recover.
+pub(crate) struct SampleMiss {
+    pub(crate) subscriber_id: SubscriberId,
+    pub(crate) source_zid: ZenohId,
+    pub(crate) source_eid: u32,
+    pub(crate) nb: u32,
+}
+
+impl SampleMiss {
+    pub(crate) fn to_wire(&self, serializer: &mut ZSerializer) {
+        serializer.serialize(self.subscriber_id);
+        serializer.serialize(self.source_zid.to_le_bytes());
+        serializer.serialize(self.source_eid);
+        serializer.serialize(self.nb);
+    }
+}
+
 pub(crate) struct Query {
     pub(crate) queryable_id: QueryableId,
     pub(crate) query_id: QueryId,
@@ -1148,6 +1335,14 @@ macro_rules! remote_message_inner {
             $($val,)*
         }
 
+        impl $enum_name {
+            $access fn name(self) -> &'static str {
+                match self {
+                    $($enum_name::$val => stringify!($val),)*
+                }
+            }
+        }
+
         impl From<$enum_name> for $typ {
             fn from(enm: $enum_name) -> Self {
                 enm as $typ
@@ -1191,7 +1386,8 @@ macro_rules! remote_message {
 
         $access enum FromWireError {
             HeaderError(zenoh_result::Error),
-            BodyError((Header, zenoh_result::Error))
+            BodyError((Header, zenoh_result::Error)),
+            FieldTooLarge((Header, zenoh_result::Error)),
         }
 
         impl From<zenoh_result::Error> for FromWireError {
@@ -1311,10 +1507,82 @@ remote_message! {
         UndeclareTransportEventsListener,
         DeclareLinkEventsListener,
         UndeclareLinkEventsListener,
+        Hello,
+        DeclareAdvancedPublisher,
+        DeclareAdvancedSubscriber,
     },
     InRemoteMessageId
 }
 
+impl InRemoteMessage {
+    /// The version of the protocol in which the message was introduced.
+    pub(crate) fn protocol_version(&self) -> u32 {
+        match self {
+            InRemoteMessage::Hello(_) => 2,
+            InRemoteMessage::DeclareAdvancedPublisher(_)
+            | InRemoteMessage::DeclareAdvancedSubscriber(_) => 3,
+            _ => LEGACY_PROTOCOL_VERSION,
+        }
+    }
+
+    pub(crate) fn payload_size(&self) -> usize {
+        let len = |v: &Option<Vec<u8>>| v.as_ref().map_or(0, Vec::len);
+        match self {
+            InRemoteMessage::Put(m) => m.payload.len(),
+            InRemoteMessage::PublisherPut(m) => m.payload.len(),
+            InRemoteMessage::Get(m) => len(&m.payload),
+            InRemoteMessage::QuerierGet(m) => len(&m.payload),
+            InRemoteMessage::ReplyOk(m) => m.payload.len(),
+            InRemoteMessage::ReplyErr(m) => m.payload.len(),
+            _ => 0,
+        }
+    }
+
+    pub(crate) fn attachment_size(&self) -> usize {
+        let attachment = match self {
+            InRemoteMessage::Put(m) => &m.attachment,
+            InRemoteMessage::Delete(m) => &m.attachment,
+            InRemoteMessage::PublisherPut(m) => &m.attachment,
+            InRemoteMessage::PublisherDelete(m) => &m.attachment,
+            InRemoteMessage::Get(m) => &m.attachment,
+            InRemoteMessage::QuerierGet(m) => &m.attachment,
+            InRemoteMessage::ReplyOk(m) => &m.attachment,
+            InRemoteMessage::ReplyDel(m) => &m.attachment,
+            _ => return 0,
+        };
+        attachment.as_ref().map_or(0, Vec::len)
+    }
+
+    /// Deserializes a message, failing if its payload or its attachment exceeds the limits.
+    /// The sizes are checked once the message is deserialized: this is a policy check on
+    /// what clients may publish, the memory used to receive a message is bounded by the
+    /// maximum WebSocket frame and message sizes.
+    pub(crate) fn from_wire_limited(
+        data: bytes::Bytes,
+        limits: &MessageLimits,
+    ) -> Result<(Header, InRemoteMessage), FromWireError> {
+        let (header, msg) = InRemoteMessage::from_wire(data)?;
+        let fields = [
+            ("payload", msg.payload_size(), limits.max_payload_size),
+            (
+                "attachment",
+                msg.attachment_size(),
+                limits.max_attachment_size,
+            ),
+        ];
+        for (field, size, max) in fields {
+            if let Some(max) = max.filter(|max| size > *max) {
+                return Err(FromWireError::FieldTooLarge((
+                    header,
+                    zerror!("The {field} of {size} bytes exceeds the maximum of {max} bytes")
+                        .into(),
+                )));
+            }
+        }
+        Ok((header, msg))
+    }
+}
+
 remote_message! {
     @to_wire
     #[repr(u8)]
@@ -1334,6 +1602,224 @@ remote_message! {
         ResponseLinks,
         TransportEventUpdate,
         LinkEventUpdate,
+        ResumeToken,
+        Batch,
+        HelloAck,
+        SampleMiss,
     },
     OutRemoteMessageId
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use zenoh::{bytes::Encoding, qos::Priority, sample::Locality};
+    use zenoh_ext::{Serialize, ZDeserializer, ZSerializer};
+
+    use super::{
+        DeclareAdvancedPublisher, DeclareAdvancedSubscriber, FromWireError, Heartbeat, History,
+        InRemoteMessage, InRemoteMessageId, PublisherId, Recovery, SequenceId, SubscriberId,
+    };
+    use crate::config::MessageLimits;
+
+    fn serialize_option<T: Serialize>(serializer: &mut ZSerializer, value: Option<T>) {
+        serializer.serialize(value.is_some());
+        if let Some(value) = value {
+            serializer.serialize(value);
+        }
+    }
+
+    fn put(payload: usize, attachment: usize, sequence_id: SequenceId) -> bytes::Bytes {
+        let mut serializer = ZSerializer::new();
+        serializer.serialize(u8::from(InRemoteMessageId::Put) | 0b10000000u8);
+        serializer.serialize(sequence_id);
+        serializer.serialize("demo/put".to_string());
+        serializer.serialize(vec![0u8; payload]);
+        serializer.serialize((0u16, String::new()));
+        serializer.serialize(true);
+        serializer.serialize(vec![0u8; attachment]);
+        serializer.serialize(false);
+        serializer.serialize(0u8);
+        serializer.finish().to_bytes().to_vec().into()
+    }
+
+    #[test]
+    fn test_from_wire_limited() {
+        let limits = MessageLimits {
+            max_payload_size: Some(10),
+            max_attachment_size: Some(4),
+            ..Default::default()
+        };
+        let (header, msg) = InRemoteMessage::from_wire_limited(put(10, 4, 1), &limits)
+            .unwrap_or_else(|_| panic!("the message is within the limits"));
+        assert_eq!(header.sequence_id, Some(1));
+        assert_eq!(msg.payload_size(), 10);
+        assert_eq!(msg.attachment_size(), 4);
+
+        for (data, field) in [(put(11, 0, 2), "payload"), (put(0, 5, 3), "attachment")] {
+            match InRemoteMessage::from_wire_limited(data, &limits) {
+                Err(FromWireError::FieldTooLarge((header, error))) => {
+                    assert!(matches!(header.content_id, InRemoteMessageId::Put));
+                    assert!(header.sequence_id.is_some());
+                    assert!(error.to_string().contains(field));
+                }
+                _ => panic!("the {field} exceeds the limit"),
+            }
+        }
+
+        assert!(
+            InRemoteMessage::from_wire_limited(put(1000, 1000, 4), &Default::default()).is_ok()
+        );
+    }
+
+    fn advanced_publisher(
+        cache_max_samples: Option<u32>,
+        heartbeat: Option<(u8, u64)>,
+        metadata: Option<&str>,
+    ) -> DeclareAdvancedPublisher {
+        let mut serializer = ZSerializer::new();
+        serializer.serialize(7u32);
+        serializer.serialize("demo/pub".to_string());
+        serializer.serialize((0u16, String::new()));
+        serializer.serialize(5u8);
+        serialize_option(&mut serializer, cache_max_samples);
+        serialize_option(&mut serializer, heartbeat);
+        serializer.serialize(true);
+        serialize_option(&mut serializer, metadata.map(str::to_string));
+        let bytes = serializer.finish();
+        let mut deserializer = ZDeserializer::new(&bytes);
+        let msg = DeclareAdvancedPublisher::from_wire(&mut deserializer).unwrap();
+        assert!(deserializer.done());
+        msg
+    }
+
+    #[test]
+    fn test_declare_advanced_publisher_from_wire() {
+        let msg = advanced_publisher(None, None, None);
+        assert_eq!(msg.id, PublisherId(7));
+        assert_eq!(msg.keyexpr.as_str(), "demo/pub");
+        assert_eq!(msg.encoding, Encoding::default());
+        assert_eq!(msg.qos.priority(), Priority::Data);
+        assert_eq!(msg.cache_max_samples, None);
+        assert!(msg.sample_miss_detection.is_none());
+        assert!(msg.publisher_detection);
+        assert_eq!(msg.publisher_detection_metadata, None);
+
+        let msg = advanced_publisher(Some(10), Some((1, 500)), Some("meta/data"));
+        assert_eq!(msg.cache_max_samples, Some(10));
+        assert!(matches!(
+            msg.sample_miss_detection,
+            Some(Heartbeat::Periodic(period)) if period == Duration::from_millis(500)
+        ));
+        assert_eq!(
+            msg.publisher_detection_metadata
+                .as_deref()
+                .map(|k| k.as_str()),
+            Some("meta/data")
+        );
+    }
+
+    #[test]
+    fn test_heartbeat_deserialize() {
+        let heartbeat = |mode: u8, period: u64| {
+            let mut serializer = ZSerializer::new();
+            serializer.serialize((mode, period));
+            let bytes = serializer.finish();
+            ZDeserializer::new(&bytes).deserialize::<Heartbeat>()
+        };
+        assert!(matches!(heartbeat(0, 0), Ok(Heartbeat::None)));
+        assert!(matches!(
+            heartbeat(1, 100),
+            Ok(Heartbeat::Periodic(period)) if period == Duration::from_millis(100)
+        ));
+        assert!(matches!(
+            heartbeat(2, 100),
+            Ok(Heartbeat::Sporadic(period)) if period == Duration::from_millis(100)
+        ));
+        // a heartbeat needs a period
+        assert!(heartbeat(1, 0).is_err());
+        assert!(heartbeat(3, 100).is_err());
+    }
+
+    fn advanced_subscriber(
+        history: Option<(bool, Option<u32>, Option<u64>)>,
+        recovery: Option<(u8, u64)>,
+        query_timeout_ms: Option<u64>,
+    ) -> DeclareAdvancedSubscriber {
+        let mut serializer = ZSerializer::new();
+        serializer.serialize(3u32);
+        serializer.serialize("demo/sub".to_string());
+        serializer.serialize(1u8);
+        serializer.serialize(history.is_some());
+        if let Some((detect_late_publishers, max_samples, max_age_ms)) = history {
+            serializer.serialize(detect_late_publishers);
+            serialize_option(&mut serializer, max_samples);
+            serialize_option(&mut serializer, max_age_ms);
+        }
+        serialize_option(&mut serializer, recovery);
+        serialize_option(&mut serializer, query_timeout_ms);
+        serializer.serialize(true);
+        let bytes = serializer.finish();
+        let mut deserializer = ZDeserializer::new(&bytes);
+        let msg = DeclareAdvancedSubscriber::from_wire(&mut deserializer).unwrap();
+        assert!(deserializer.done());
+        msg
+    }
+
+    #[test]
+    fn test_declare_advanced_subscriber_from_wire() {
+        let msg = advanced_subscriber(None, None, None);
+        assert_eq!(msg.id, SubscriberId(3));
+        assert_eq!(msg.keyexpr.as_str(), "demo/sub");
+        assert_eq!(msg.allowed_origin, Locality::Remote);
+        assert!(msg.history.is_none());
+        assert!(msg.recovery.is_none());
+        assert_eq!(msg.query_timeout_ms, None);
+        assert!(msg.subscriber_detection);
+
+        let msg = advanced_subscriber(Some((true, Some(10), Some(2000))), Some((0, 0)), Some(500));
+        assert!(matches!(
+            msg.history,
+            Some(History {
+                detect_late_publishers: true,
+                max_samples: Some(10),
+                max_age_ms: Some(2000),
+            })
+        ));
+        assert!(matches!(msg.recovery, Some(Recovery::Heartbeat)));
+        assert_eq!(msg.query_timeout_ms, Some(500));
+
+        let msg = advanced_subscriber(Some((false, None, None)), Some((1, 250)), None);
+        assert!(matches!(
+            msg.history,
+            Some(History {
+                detect_late_publishers: false,
+                max_samples: None,
+                max_age_ms: None,
+            })
+        ));
+        assert!(matches!(
+            msg.recovery,
+            Some(Recovery::PeriodicQueries(period)) if period == Duration::from_millis(250)
+        ));
+    }
+
+    #[test]
+    fn test_recovery_deserialize() {
+        let recovery = |mode: u8, period: u64| {
+            let mut serializer = ZSerializer::new();
+            serializer.serialize((mode, period));
+            let bytes = serializer.finish();
+            ZDeserializer::new(&bytes).deserialize::<Recovery>()
+        };
+        assert!(matches!(recovery(0, 0), Ok(Recovery::Heartbeat)));
+        assert!(matches!(
+            recovery(1, 100),
+            Ok(Recovery::PeriodicQueries(period)) if period == Duration::from_millis(100)
+        ));
+        // periodic queries need a period
+        assert!(recovery(1, 0).is_err());
+        assert!(recovery(2, 100).is_err());
+    }
+}
@@ -19,35 +19,50 @@
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
 
 use std::{
-    collections::HashMap,
-    fs::File,
+    collections::{BTreeMap, HashMap},
     future::Future,
-    io::{self, BufReader},
-    net::SocketAddr,
-    path::Path,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
-use futures::{future, pin_mut, SinkExt, StreamExt, TryStreamExt};
-use interface::{InRemoteMessage, OutRemoteMessage, SequenceId};
+use acl::{Acl, ClientAcl};
+use auth::{Authenticator, TOKENS_FILE_POLL_INTERVAL};
+use batching::Batcher;
+use commands::{command_channel, AdminCommand, CommandSender};
+use compression::Compressor;
+use connections::{ClientCount, Keepalive, KeepaliveEvent};
+use futures::{future, SinkExt, StreamExt, TryStreamExt};
+use interface::{
+    InRemoteMessage, InRemoteMessageId, OutRemoteMessage, OutRemoteMessageId, SequenceId,
+};
+use listeners::{prepare_listeners, Connection, PreparedListener, Server};
+use metrics::ServerMetrics;
+use outbound::{outbound_channel, OutboundMessage, OutboundQueueDepth};
+use presence::ClientPresence;
+use protocol::{Negotiation, LEGACY_PROTOCOL_VERSION};
+use quotas::{ClientQuotas, EntityUsage, GlobalQuotas};
+use ratelimit::RateLimits;
+use reconfigure::{check_config_change, ConfigUpdate};
 use remote_state::RemoteState;
-use rustls_pemfile::{certs, private_key};
+use resumption::{ClientSession, SuspendedSessions};
 use serde::Serialize;
+use stats::{unix_time_millis, ClientStats};
+use tls::ReloadableTlsAcceptor;
 use tokio::{
-    net::{TcpListener, TcpStream},
+    net::TcpStream,
     select,
-    sync::RwLock,
+    sync::{mpsc, watch, Notify, RwLock},
     task::JoinHandle,
 };
-use tokio_rustls::{
-    rustls::{
+use tokio_rustls::{rustls, server::TlsStream, TlsAcceptor};
+use tokio_tungstenite::{
+    tungstenite::{
         self,
-        pki_types::{CertificateDer, PrivateKeyDer},
+        handshake::server::{ErrorResponse, Request, Response},
+        protocol::{frame::coding::CloseCode, CloseFrame, Message, WebSocketConfig},
     },
-    server::TlsStream,
-    TlsAcceptor,
+    WebSocketStream,
 };
-use tokio_tungstenite::tungstenite::protocol::Message;
 use uuid::Uuid;
 use zenoh::{
     bytes::{Encoding, ZBytes},
@@ -60,20 +75,63 @@ use zenoh::{
         keyexpr, OwnedKeyExpr,
     },
     query::Query,
+    sample::{Locality, SampleKind},
 };
 use zenoh_plugin_trait::{plugin_long_version, plugin_version, Plugin, PluginControl};
 use zenoh_result::{bail, zerror, ZResult};
 use zenoh_util::ffi::JsonKeyValueMap;
 
+mod acl;
+
+mod auth;
+
+mod batching;
+
+mod commands;
+
+mod compression;
+
 mod config;
 pub use config::Config;
+use config::{Listener, MessageLimits};
 
-use crate::interface::{LivelinessTokenId, PublisherId, QuerierId, QueryableId, SubscriberId};
+use crate::interface::{
+    LinkEventsListenerId, LivelinessTokenId, MatchingListenerId, PublisherId, QuerierId, QueryId,
+    QueryableId, SubscriberId, TransportEventsListenerId,
+};
+
+mod connections;
+
+mod http;
 
 mod interface;
 
+mod listeners;
+
+mod metrics;
+
+mod origins;
+
+mod outbound;
+
+mod presence;
+
+mod protocol;
+
+mod quotas;
+
+mod ratelimit;
+
+mod reconfigure;
+
 mod remote_state;
 
+mod resumption;
+
+mod stats;
+
+mod tls;
+
 kedefine!(
     // Admin space key expressions of plugin's version
     pub ke_admin_version: "${plugin_status_key:**}/__version__",
@@ -101,6 +159,7 @@ lazy_static::lazy_static! {
 enum AdminRef {
     Config,
     Version,
+    Quotas,
 }
 
 #[inline(always)]
@@ -129,16 +188,6 @@ pub fn spawn_future(fut: impl Future<Output = ()> + 'static + std::marker::Send)
     }
 }
 
-fn load_certs(path: &Path) -> io::Result<Vec<CertificateDer<'static>>> {
-    certs(&mut BufReader::new(File::open(path)?)).collect()
-}
-
-fn load_key(path: &Path) -> io::Result<PrivateKeyDer<'static>> {
-    private_key(&mut BufReader::new(File::open(path)?))
-        .unwrap()
-        .ok_or_else(|| io::Error::other("No private key found"))
-}
-
 pub struct RemoteApiPlugin;
 
 #[cfg(feature = "dynamic_plugin")]
@@ -168,40 +217,82 @@ impl Plugin for RemoteApiPlugin {
 
         let conf: Config = serde_json::from_value(plugin_conf)
             .map_err(|e| zerror!("Plugin `{}` configuration error: {}", name, e))?;
+        protocol::check_min_version(conf.min_protocol_version)
+            .map_err(|e| zerror!("Plugin `{}` configuration error: {}", name, e))?;
 
-        let wss_config: Option<(Vec<CertificateDer<'_>>, PrivateKeyDer<'_>)> =
-            match conf.secure_websocket.clone() {
-                Some(wss_config) => {
-                    tracing::info!("Loading certs from : {} ...", wss_config.certificate_path);
-                    let certs = load_certs(Path::new(&wss_config.certificate_path))
-                        .map_err(|err| zerror!("Could not Load WSS Cert `{}`", err))?;
-                    tracing::info!(
-                        "Loading Private Key from : {} ...",
-                        wss_config.private_key_path
-                    );
-                    let key = load_key(Path::new(&wss_config.private_key_path))
-                        .map_err(|err| zerror!("Could not Load WSS Private Key `{}`", err))?;
-                    Some((certs, key))
-                }
-                None => None,
-            };
+        let mut listeners = prepare_listeners(&conf)?;
+        for listener in &mut listeners {
+            listener.bind()?;
+        }
 
-        spawn_runtime(run(runtime.clone(), conf, wss_config));
-        Ok(Box::new(RunningPlugin(RemoteAPIPlugin)))
+        let authenticator = match &conf.authentication {
+            Some(auth_config) => {
+                let mut authenticator = Authenticator::new(auth_config)
+                    .map_err(|err| zerror!("Could not set up authentication `{}`", err))?;
+                authenticator.watch(TOKENS_FILE_POLL_INTERVAL);
+                Some(authenticator)
+            }
+            None => None,
+        };
+
+        let acl = match &conf.access_control {
+            Some(acl_config) => Some(
+                Acl::new(acl_config)
+                    .map_err(|err| zerror!("Could not set up access control `{}`", err))?,
+            ),
+            None => None,
+        };
+
+        let rate_limits = RateLimits::new(&conf.rate_limits)
+            .map_err(|err| zerror!("Could not set up rate limits `{}`", err))?;
+
+        let presence = match &conf.client_liveliness {
+            Some(liveliness_config) => Some(
+                ClientPresence::new(liveliness_config)
+                    .map_err(|err| zerror!("Could not set up client liveliness `{}`", err))?,
+            ),
+            None => None,
+        };
+
+        let (config_updates_tx, config_updates_rx) = mpsc::unbounded_channel();
+        spawn_runtime(run(
+            runtime.clone(),
+            conf,
+            config_updates_rx,
+            listeners,
+            authenticator,
+            acl,
+            rate_limits,
+            presence,
+        ));
+        Ok(Box::new(RunningPlugin {
+            config_updates: config_updates_tx,
+        }))
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
     runtime: DynamicRuntime,
     config: Config,
-    opt_certs: Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>,
+    config_updates: mpsc::UnboundedReceiver<ConfigUpdateRequest>,
+    listeners: Vec<PreparedListener>,
+    authenticator: Option<Authenticator>,
+    acl: Option<Acl>,
+    rate_limits: RateLimits,
+    presence: Option<ClientPresence>,
 ) {
     let state_map = Arc::new(RwLock::new(HashMap::new()));
 
     // Return WebServer And State
     let remote_api_runtime = RemoteAPIRuntime {
         config: Arc::new(config),
-        wss_certs: opt_certs,
+        config_updates,
+        listeners,
+        authenticator: authenticator.map(Arc::new),
+        acl: acl.map(Arc::new),
+        rate_limits,
+        presence,
         zenoh_runtime: runtime,
         state_map,
     };
@@ -211,70 +302,237 @@ pub async fn run(
 
 struct RemoteAPIRuntime {
     config: Arc<Config>,
-    wss_certs: Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>,
+    config_updates: mpsc::UnboundedReceiver<ConfigUpdateRequest>,
+    listeners: Vec<PreparedListener>,
+    authenticator: Option<Arc<Authenticator>>,
+    acl: Option<Arc<Acl>>,
+    rate_limits: RateLimits,
+    presence: Option<ClientPresence>,
     zenoh_runtime: DynamicRuntime,
     state_map: StateMap,
 }
 
 impl RemoteAPIRuntime {
     async fn run(self) {
-        let run_websocket_server = run_websocket_server(
-            &self.config.websocket_port,
+        let quotas = Arc::new(GlobalQuotas::new(self.config.quotas.as_ref()));
+        // the configuration currently applied, updated at runtime through `config_checker`
+        let (config_tx, config_rx) = watch::channel(self.config);
+
+        let context = Arc::new(ServerContext {
+            zenoh_runtime: self.zenoh_runtime.clone(),
+            state_map: self.state_map.clone(),
+            config: config_rx.clone(),
+            authenticator: self.authenticator,
+            acl: self.acl,
+            suspended: SuspendedSessions::default(),
+            quotas: quotas.clone(),
+            rate_limits: std::sync::RwLock::new(self.rate_limits),
+            presence: self.presence,
+            clients: ClientCount::default(),
+            metrics: ServerMetrics::new(self.state_map.clone()),
+        });
+        let run_listeners = run_listeners(context, self.listeners, config_tx, self.config_updates);
+
+        let run_admin_space_queryable = run_admin_space_queryable(
             self.zenoh_runtime.clone(),
             self.state_map.clone(),
-            self.wss_certs,
+            config_rx,
+            quotas,
         );
-
-        let config = (*self.config).clone();
-
-        let run_admin_space_queryable =
-            run_admin_space_queryable(self.zenoh_runtime.clone(), self.state_map.clone(), config);
+        let run_admin_space_commands =
+            run_admin_space_commands(self.zenoh_runtime.clone(), self.state_map.clone());
 
         select!(
-            _ = run_websocket_server => {},
+            _ = run_listeners => {},
             _ = run_admin_space_queryable => {},
+            _ = run_admin_space_commands => {},
         );
     }
 }
 
+#[derive(Debug, Serialize, Clone)]
+struct AdminSpaceQos {
+    priority: String,
+    congestion_control: String,
+    express: bool,
+    reliability: String,
+}
+
+/// An entity declared by a client, as shown in the admin space.
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct AdminSpaceEntity {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key_expr: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    qos: Option<AdminSpaceQos>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    locality: Option<String>,
+    /// The settings specific to the kind of entity.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    settings: BTreeMap<&'static str, serde_json::Value>,
+    /// In milliseconds since the UNIX epoch.
+    declared_at: u64,
+}
+
+impl AdminSpaceEntity {
+    pub(crate) fn new(key_expr: Option<&str>) -> Self {
+        AdminSpaceEntity {
+            key_expr: key_expr.map(str::to_string),
+            qos: None,
+            locality: None,
+            settings: BTreeMap::new(),
+            declared_at: unix_time_millis(),
+        }
+    }
+
+    /// Sets the QoS of the entity, and the locality of its destinations.
+    pub(crate) fn qos(mut self, qos: &interface::Qos) -> Self {
+        self.qos = Some(AdminSpaceQos {
+            priority: format!("{:?}", qos.priority()),
+            congestion_control: format!("{:?}", qos.congestion_control()),
+            express: qos.express(),
+            reliability: format!("{:?}", qos.reliability()),
+        });
+        self.locality(qos.locality())
+    }
+
+    pub(crate) fn locality(mut self, locality: Locality) -> Self {
+        self.locality = Some(format!("{locality:?}"));
+        self
+    }
+
+    pub(crate) fn setting(
+        mut self,
+        name: &'static str,
+        value: impl Into<serde_json::Value>,
+    ) -> Self {
+        self.settings.insert(name, value.into());
+        self
+    }
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub(crate) struct AdminSpaceClient {
     uuid: String,
-    remote_address: SocketAddr,
-    publishers: HashMap<PublisherId, String>,
-    subscribers: HashMap<SubscriberId, String>,
-    queryables: HashMap<QueryableId, String>,
-    queriers: HashMap<QuerierId, String>,
-    liveliness_tokens: HashMap<LivelinessTokenId, String>,
+    remote_address: String,
+    listener: String,
+    user: Option<String>,
+    cert_subject: Option<String>,
+    publishers: HashMap<PublisherId, AdminSpaceEntity>,
+    subscribers: HashMap<SubscriberId, AdminSpaceEntity>,
+    queryables: HashMap<QueryableId, AdminSpaceEntity>,
+    queriers: HashMap<QuerierId, AdminSpaceEntity>,
+    liveliness_tokens: HashMap<LivelinessTokenId, AdminSpaceEntity>,
+    liveliness_subscribers: HashMap<SubscriberId, AdminSpaceEntity>,
+    matching_listeners: HashMap<MatchingListenerId, AdminSpaceEntity>,
+    transport_events_listeners: HashMap<TransportEventsListenerId, AdminSpaceEntity>,
+    link_events_listeners: HashMap<LinkEventsListenerId, AdminSpaceEntity>,
+    /// The queries received by the queryables of the client, which it didn't finish answering.
+    pending_queries: HashMap<QueryId, AdminSpaceEntity>,
+    /// The number of entities of each type counted in the quotas.
+    entity_usage: EntityUsage,
+    outbound_queue_overflows: u64,
+    /// The number of messages rejected or connections closed for exceeding the message limits.
+    oversized_messages: u64,
+    stats: ClientStats,
+    #[serde(skip)]
+    commands: CommandSender,
+    #[serde(skip)]
+    outbound_queue: Option<OutboundQueueDepth>,
 }
 
 impl AdminSpaceClient {
-    pub(crate) fn new(uuid: String, remote_address: SocketAddr) -> Self {
+    pub(crate) fn new(
+        uuid: String,
+        remote_address: String,
+        listener: String,
+        user: Option<String>,
+        cert_subject: Option<String>,
+        commands: CommandSender,
+    ) -> Self {
         AdminSpaceClient {
             uuid,
             remote_address,
+            listener,
+            user,
+            cert_subject,
             publishers: HashMap::new(),
             subscribers: HashMap::new(),
             queryables: HashMap::new(),
             queriers: HashMap::new(),
             liveliness_tokens: HashMap::new(),
+            liveliness_subscribers: HashMap::new(),
+            matching_listeners: HashMap::new(),
+            transport_events_listeners: HashMap::new(),
+            link_events_listeners: HashMap::new(),
+            pending_queries: HashMap::new(),
+            entity_usage: EntityUsage::new(),
+            outbound_queue_overflows: 0,
+            oversized_messages: 0,
+            stats: ClientStats::new(),
+            commands,
+            outbound_queue: None,
         }
     }
 
-    pub(crate) fn register_publisher(&mut self, id: PublisherId, key_expr: &str) {
-        self.publishers.insert(id, key_expr.to_string());
+    pub(crate) fn register_publisher(&mut self, id: PublisherId, entity: AdminSpaceEntity) {
+        self.publishers.insert(id, entity);
+    }
+
+    pub(crate) fn register_subscriber(&mut self, id: SubscriberId, entity: AdminSpaceEntity) {
+        self.subscribers.insert(id, entity);
+    }
+
+    pub(crate) fn register_queryable(&mut self, id: QueryableId, entity: AdminSpaceEntity) {
+        self.queryables.insert(id, entity);
+    }
+
+    pub(crate) fn register_querier(&mut self, id: QuerierId, entity: AdminSpaceEntity) {
+        self.queriers.insert(id, entity);
     }
 
-    pub(crate) fn register_subscriber(&mut self, id: SubscriberId, key_expr: &str) {
-        self.subscribers.insert(id, key_expr.to_string());
+    pub(crate) fn register_liveliness_token(
+        &mut self,
+        id: LivelinessTokenId,
+        entity: AdminSpaceEntity,
+    ) {
+        self.liveliness_tokens.insert(id, entity);
     }
 
-    pub(crate) fn register_queryable(&mut self, id: QueryableId, key_expr: &str) {
-        self.queryables.insert(id, key_expr.to_string());
+    pub(crate) fn register_liveliness_subscriber(
+        &mut self,
+        id: SubscriberId,
+        entity: AdminSpaceEntity,
+    ) {
+        self.liveliness_subscribers.insert(id, entity);
     }
 
-    pub(crate) fn register_querier(&mut self, id: QuerierId, key_expr: &str) {
-        self.queriers.insert(id, key_expr.to_string());
+    pub(crate) fn register_matching_listener(
+        &mut self,
+        id: MatchingListenerId,
+        entity: AdminSpaceEntity,
+    ) {
+        self.matching_listeners.insert(id, entity);
+    }
+
+    pub(crate) fn register_transport_events_listener(
+        &mut self,
+        id: TransportEventsListenerId,
+        entity: AdminSpaceEntity,
+    ) {
+        self.transport_events_listeners.insert(id, entity);
+    }
+
+    pub(crate) fn register_link_events_listener(
+        &mut self,
+        id: LinkEventsListenerId,
+        entity: AdminSpaceEntity,
+    ) {
+        self.link_events_listeners.insert(id, entity);
+    }
+
+    pub(crate) fn register_pending_query(&mut self, id: QueryId, entity: AdminSpaceEntity) {
+        self.pending_queries.insert(id, entity);
     }
 
     pub(crate) fn unregister_publisher(&mut self, id: PublisherId) {
@@ -293,15 +551,98 @@ impl AdminSpaceClient {
         self.queriers.remove(&id);
     }
 
+    pub(crate) fn unregister_liveliness_token(&mut self, id: LivelinessTokenId) {
+        self.liveliness_tokens.remove(&id);
+    }
+
+    pub(crate) fn unregister_liveliness_subscriber(&mut self, id: SubscriberId) {
+        self.liveliness_subscribers.remove(&id);
+    }
+
+    pub(crate) fn unregister_matching_listener(&mut self, id: MatchingListenerId) {
+        self.matching_listeners.remove(&id);
+    }
+
+    pub(crate) fn unregister_transport_events_listener(&mut self, id: TransportEventsListenerId) {
+        self.transport_events_listeners.remove(&id);
+    }
+
+    pub(crate) fn unregister_link_events_listener(&mut self, id: LinkEventsListenerId) {
+        self.link_events_listeners.remove(&id);
+    }
+
+    pub(crate) fn unregister_pending_query(&mut self, id: QueryId) {
+        self.pending_queries.remove(&id);
+    }
+
+    pub(crate) fn clear_pending_queries(&mut self) {
+        self.pending_queries.clear();
+    }
+
+    pub(crate) fn set_entity_usage(&mut self, usage: EntityUsage) {
+        self.entity_usage = usage;
+    }
+
+    pub(crate) fn register_outbound_queue_overflow(&mut self) {
+        self.outbound_queue_overflows += 1;
+    }
+
+    pub(crate) fn register_oversized_message(&mut self) {
+        self.oversized_messages += 1;
+    }
+
+    pub(crate) fn register_connection(&mut self) {
+        self.stats.connected();
+    }
+
+    pub(crate) fn register_received(&mut self, kind: InRemoteMessageId, bytes: usize) {
+        self.stats.received(kind.name(), bytes);
+    }
+
+    pub(crate) fn register_sent(&mut self, kind: OutRemoteMessageId, bytes: usize) {
+        self.stats.sent(kind.name(), bytes);
+    }
+
+    pub(crate) fn register_error(&mut self) {
+        self.stats.error();
+    }
+
+    pub(crate) fn stats(&self) -> &ClientStats {
+        &self.stats
+    }
+
+    pub(crate) fn set_outbound_queue(&mut self, depth: OutboundQueueDepth) {
+        self.outbound_queue = Some(depth);
+    }
+
+    pub(crate) fn outbound_queue_depth(&self) -> usize {
+        self.outbound_queue
+            .as_ref()
+            .map_or(0, OutboundQueueDepth::get)
+    }
+
+    pub(crate) fn pending_query_count(&self) -> usize {
+        self.pending_queries.len()
+    }
+
     pub(crate) fn id(&self) -> &str {
         &self.uuid
     }
+
+    pub(crate) fn listener(&self) -> &str {
+        &self.listener
+    }
+
+    pub(crate) fn commands(&self) -> &CommandSender {
+        &self.commands
+    }
 }
 
 async fn run_admin_space_queryable(
     zenoh_runtime: DynamicRuntime,
     state_map: StateMap,
-    config: Config,
+    config: watch::Receiver<Arc<Config>>,
+    quotas: Arc<GlobalQuotas>,
 ) {
     let session = match zenoh::session::init(zenoh_runtime).await {
         Ok(session) => session,
@@ -327,6 +668,10 @@ async fn run_admin_space_queryable(
         &admin_prefix / unsafe { keyexpr::from_str_unchecked("version") },
         AdminRef::Version,
     );
+    admin_space.insert(
+        &admin_prefix / unsafe { keyexpr::from_str_unchecked("quotas") },
+        AdminRef::Quotas,
+    );
 
     let admin_keyexpr_expr = (&admin_prefix) / *KE_ANY_N_SEGMENT;
 
@@ -350,34 +695,42 @@ async fn run_admin_space_queryable(
                         drop(read_guard);
                         send_reply(admin_space_clients, query, query_ke).await;
                     } else {
+                        let config = config.borrow().clone();
                         for (ke, admin_ref) in admin_space.iter() {
                             if query_ke.intersects(ke) {
-                                send_admin_reply(&query, ke, admin_ref, &config).await;
+                                send_admin_reply(&query, ke, admin_ref, &config, &quotas).await;
                             }
                         }
                     }
                 } else {
                     let own_ke: OwnedKeyExpr = query_ke.to_owned();
                     if own_ke.contains("config") {
-                        send_admin_reply(&query, &own_ke, &AdminRef::Config, &config).await;
+                        let config = config.borrow().clone();
+                        send_admin_reply(&query, &own_ke, &AdminRef::Config, &config, &quotas)
+                            .await;
+                    }
+                    if let Some(admin_ref @ AdminRef::Quotas) = admin_space.get(&own_ke) {
+                        let config = config.borrow().clone();
+                        send_admin_reply(&query, &own_ke, admin_ref, &config, &quotas).await;
                     }
                     if own_ke.contains("client") {
-                        let mut opt_id = None;
-                        let split = own_ke.split('/');
-                        let mut next_is_id = false;
-                        for elem in split {
-                            if next_is_id {
-                                opt_id = Some(elem);
-                            } else if elem.contains("client") {
-                                next_is_id = true;
-                            }
-                        }
+                        // clients/<uuid> or clients/<uuid>/stats
+                        let mut chunks = own_ke
+                            .split('/')
+                            .skip_while(|elem| !elem.contains("client"))
+                            .skip(1);
+                        let opt_id = chunks.next();
+                        let stats = chunks.next() == Some("stats");
                         if let Some(id) = opt_id {
                             let read_guard = state_map.read().await;
                             if let Some(state) = read_guard.get(id) {
                                 let state = state.lock().unwrap().clone();
                                 drop(read_guard);
-                                send_reply(state, query, own_ke).await;
+                                if stats {
+                                    send_reply(state.stats(), query, own_ke).await;
+                                } else {
+                                    send_reply(state, query, own_ke).await;
+                                }
                             }
                         }
                     }
@@ -390,6 +743,65 @@ async fn run_admin_space_queryable(
     }
 }
 
+/// Disconnects the clients whose key is deleted from the admin space, and revokes the entities
+/// whose key is deleted, if the admin space is writable.
+async fn run_admin_space_commands(zenoh_runtime: DynamicRuntime, state_map: StateMap) {
+    let session = match zenoh::session::init(zenoh_runtime.clone()).await {
+        Ok(session) => session,
+        Err(err) => {
+            tracing::error!("Unable to get Zenoh session from Runtime {err}");
+            return;
+        }
+    };
+
+    let admin_prefix = keformat!(
+        ke_admin_prefix::formatter(),
+        zenoh_id = session.zid().into_keyexpr()
+    )
+    .unwrap();
+    let clients_prefix = &admin_prefix / unsafe { keyexpr::from_str_unchecked("clients") };
+
+    let admin_subscriber = session
+        .declare_subscriber(&clients_prefix / *KE_ANY_N_SEGMENT)
+        .await
+        .expect("Failed to create AdminSpace Subscriber");
+
+    while let Ok(sample) = admin_subscriber.recv_async().await {
+        if sample.kind() != SampleKind::Delete {
+            continue;
+        }
+        let key_expr = sample.key_expr();
+        // read on every command, as the admin space permissions can be changed at runtime
+        let writable = zenoh_runtime
+            .get_config()
+            .get_typed::<bool>("adminspace/permissions/write")
+            .unwrap_or(false);
+        if !writable {
+            tracing::warn!(
+                "Received DELETE on '{key_expr}' but adminspace.permissions.write=false in configuration"
+            );
+            continue;
+        }
+        // clients/<uuid> or clients/<uuid>/<kind>/<id>
+        let Some(suffix) = key_expr.as_str().strip_prefix(clients_prefix.as_str()) else {
+            continue;
+        };
+        let segments = suffix.split('/').skip(1).collect::<Vec<_>>();
+        let Some((id, segments)) = segments.split_first() else {
+            continue;
+        };
+        let Some(command) = AdminCommand::parse(segments) else {
+            tracing::warn!("AdminSpace: unknown command on '{key_expr}'");
+            continue;
+        };
+        match state_map.read().await.get(*id) {
+            Some(client) => client.lock().unwrap().commands().send(command),
+            None => tracing::warn!("AdminSpace: client {id} does not exist"),
+        }
+    }
+    tracing::warn!("Admin Space subscriber was closed!");
+}
+
 async fn send_reply<T>(reply: T, query: Query, query_ke: OwnedKeyExpr)
 where
     T: Sized + Serialize,
@@ -415,6 +827,7 @@ async fn send_admin_reply(
     key_expr: &keyexpr,
     admin_ref: &AdminRef,
     config: &Config,
+    quotas: &GlobalQuotas,
 ) {
     let z_bytes: ZBytes = match admin_ref {
         AdminRef::Version => match serde_json::to_value(RemoteApiPlugin::PLUGIN_LONG_VERSION) {
@@ -430,6 +843,19 @@ async fn send_admin_reply(
                 return;
             }
         },
+        AdminRef::Quotas => match quotas.status() {
+            Ok(v) => match serde_json::to_vec(&v) {
+                Ok(value) => ZBytes::from(value),
+                Err(e) => {
+                    tracing::warn!("Error transforming JSON to ZBytes: {}", e);
+                    return;
+                }
+            },
+            Err(e) => {
+                tracing::error!("INTERNAL ERROR serializing quotas as JSON: {}", e);
+                return;
+            }
+        },
         AdminRef::Config => match serde_json::to_value(config) {
             Ok(v) => match serde_json::to_vec(&v) {
                 Ok(value) => ZBytes::from(value),
@@ -453,10 +879,15 @@ async fn send_admin_reply(
     }
 }
 
-struct RemoteAPIPlugin;
+/// A configuration change, with the channel on which the result of applying it is reported.
+type ConfigUpdateRequest = (ConfigUpdate, std::sync::mpsc::Sender<ZResult<()>>);
+
+/// How long a configuration change may take to be applied by the WebSocket servers.
+const CONFIG_UPDATE_TIMEOUT: Duration = Duration::from_secs(10);
 
-#[allow(dead_code)]
-struct RunningPlugin(RemoteAPIPlugin);
+struct RunningPlugin {
+    config_updates: mpsc::UnboundedSender<ConfigUpdateRequest>,
+}
 
 impl PluginControl for RunningPlugin {}
 
@@ -464,10 +895,26 @@ impl RunningPluginTrait for RunningPlugin {
     fn config_checker(
         &self,
         _path: &str,
-        _current: &JsonKeyValueMap,
-        _new: &JsonKeyValueMap,
+        current: &JsonKeyValueMap,
+        new: &JsonKeyValueMap,
     ) -> ZResult<Option<JsonKeyValueMap>> {
-        bail!("Runtime configuration change not supported");
+        let update = check_config_change(&current.into(), &new.into())?;
+        let (result_tx, result_rx) = std::sync::mpsc::channel();
+        if self.config_updates.send((update, result_tx)).is_err() {
+            bail!("Remote API server is not running");
+        }
+        // the new listeners are bound when the change is applied, which may fail
+        let wait = || result_rx.recv_timeout(CONFIG_UPDATE_TIMEOUT);
+        let result = match tokio::runtime::Handle::try_current() {
+            Ok(rt) if rt.runtime_flavor() != tokio::runtime::RuntimeFlavor::CurrentThread => {
+                tokio::task::block_in_place(wait)
+            }
+            _ => wait(),
+        };
+        match result {
+            Ok(result) => result.map(|()| None),
+            Err(_) => bail!("Remote API server didn't apply the configuration change in time"),
+        }
     }
 }
 
@@ -478,43 +925,465 @@ pub trait Streamable:
 {
 }
 impl Streamable for TcpStream {}
-impl Streamable for TlsStream<TcpStream> {}
+#[cfg(unix)]
+impl Streamable for tokio::net::UnixStream {}
+impl<S: Streamable> Streamable for TlsStream<S> {}
+impl<S: Streamable> Streamable for http::PrefixedStream<S> {}
 
-// Listen on the Zenoh Session
-async fn run_websocket_server(
-    ws_port: &String,
+/// What the WebSocket servers share.
+struct ServerContext {
     zenoh_runtime: DynamicRuntime,
     state_map: StateMap,
-    opt_certs: Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>,
+    config: watch::Receiver<Arc<Config>>,
+    authenticator: Option<Arc<Authenticator>>,
+    acl: Option<Arc<Acl>>,
+    suspended: SuspendedSessions,
+    quotas: Arc<GlobalQuotas>,
+    rate_limits: std::sync::RwLock<RateLimits>,
+    presence: Option<ClientPresence>,
+    clients: ClientCount,
+    metrics: ServerMetrics,
+}
+
+/// A running WebSocket server, stopped when dropped.
+struct ListenerHandle {
+    config: Listener,
+    tls_config: Option<Arc<rustls::ServerConfig>>,
+    task: JoinHandle<()>,
+    updates: mpsc::UnboundedSender<PreparedListener>,
+}
+
+impl ListenerHandle {
+    /// Stops the server and waits for its socket to be closed.
+    async fn stop(mut self) {
+        self.task.abort();
+        let _ = (&mut self.task).await;
+    }
+
+    fn update(&mut self, listener: PreparedListener) {
+        self.config = listener.config.clone();
+        self.tls_config = listener.tls_config.clone();
+        let _ = self.updates.send(listener);
+    }
+}
+
+impl Drop for ListenerHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Starts the WebSocket server of a listener on its socket.
+type StartServer = dyn Fn(PreparedListener, Server, mpsc::UnboundedReceiver<PreparedListener>) -> JoinHandle<()>
+    + Send
+    + Sync;
+
+/// The running WebSocket servers, by listener address.
+struct Listeners {
+    running: HashMap<String, ListenerHandle>,
+    start: Box<StartServer>,
+}
+
+impl Listeners {
+    fn new(start: Box<StartServer>) -> Self {
+        Listeners {
+            running: HashMap::new(),
+            start,
+        }
+    }
+
+    /// Binds the address of a listener, unless it was bound already.
+    fn bind(listener: &mut PreparedListener) -> ZResult<Server> {
+        Server::new(&listener.config, listener.socket.take())
+    }
+
+    fn spawn(&mut self, listener: PreparedListener, server: Server) {
+        tracing::info!("Listening on {}", listener.config.bind);
+        let (updates, updates_rx) = mpsc::unbounded_channel();
+        let handle = ListenerHandle {
+            config: listener.config.clone(),
+            tls_config: listener.tls_config.clone(),
+            task: (self.start)(listener, server, updates_rx),
+            updates,
+        };
+        self.running.insert(handle.config.bind.clone(), handle);
+    }
+
+    /// Runs the listeners of a configuration: binds the new ones, updates the running ones and
+    /// stops the others. A listener being removed is only stopped before the new ones are bound
+    /// if one of them can't bind its address, in case they share it. If a new listener can't be
+    /// bound, the previous listeners are kept.
+    async fn apply(&mut self, listeners: Vec<PreparedListener>) -> ZResult<()> {
+        // the servers which stopped on an error are started again
+        self.running.retain(|bind, handle| {
+            let running = !handle.task.is_finished();
+            if !running {
+                tracing::warn!("Listener on {bind} stopped");
+            }
+            running
+        });
+        let (updated, added): (Vec<_>, Vec<_>) = listeners
+            .into_iter()
+            .partition(|l| self.running.contains_key(&l.config.bind));
+        let removed = self
+            .running
+            .keys()
+            .filter(|bind| !updated.iter().any(|l| &l.config.bind == *bind))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let mut servers = Vec::new();
+        let mut failed = Vec::new();
+        for mut listener in added {
+            match Self::bind(&mut listener) {
+                Ok(server) => servers.push((listener, server)),
+                Err(err) => failed.push((listener, err)),
+            }
+        }
+        let mut stopped = Vec::new();
+        if !failed.is_empty() && !removed.is_empty() {
+            for bind in &removed {
+                if let Some(handle) = self.running.remove(bind) {
+                    stopped.push((handle.config.clone(), handle.tls_config.clone()));
+                    handle.stop().await;
+                }
+            }
+            for (mut listener, err) in std::mem::take(&mut failed) {
+                match Self::bind(&mut listener) {
+                    Ok(server) => servers.push((listener, server)),
+                    Err(_) => failed.push((listener, err)),
+                }
+            }
+        }
+        if let Some((listener, err)) = failed.into_iter().next() {
+            drop(servers);
+            for (config, tls_config) in stopped {
+                let mut listener = PreparedListener {
+                    config,
+                    socket: None,
+                    tls_config,
+                };
+                match Self::bind(&mut listener) {
+                    Ok(server) => self.spawn(listener, server),
+                    Err(err) => tracing::error!("Unable to restore listener: {err}"),
+                }
+            }
+            bail!(
+                "Unable to start listener on {}: {err}",
+                listener.config.bind
+            );
+        }
+
+        // Connected clients are not affected: their tasks don't depend on the listener
+        for bind in removed {
+            if let Some(handle) = self.running.remove(&bind) {
+                tracing::info!("Closing listener on {bind}");
+                handle.stop().await;
+            }
+        }
+        for listener in updated {
+            if let Some(handle) = self.running.get_mut(&listener.config.bind) {
+                handle.update(listener);
+            }
+        }
+        for (listener, server) in servers {
+            self.spawn(listener, server);
+        }
+        Ok(())
+    }
+}
+
+// Runs a WebSocket server per listener, and applies the configuration changes
+async fn run_listeners(
+    context: Arc<ServerContext>,
+    listeners: Vec<PreparedListener>,
+    config_tx: watch::Sender<Arc<Config>>,
+    mut config_updates: mpsc::UnboundedReceiver<ConfigUpdateRequest>,
 ) {
-    let mut opt_tls_acceptor: Option<TlsAcceptor> = None;
+    let start = {
+        let context = context.clone();
+        move |listener, server, updates| {
+            spawn_future(run_websocket_server(
+                context.clone(),
+                listener,
+                server,
+                updates,
+            ))
+        }
+    };
+    let mut running = Listeners::new(Box::new(start));
+    if let Err(err) = running.apply(listeners).await {
+        tracing::error!("{err}");
+    }
 
-    if let Some((certs, key)) = opt_certs {
-        let config = rustls::ServerConfig::builder()
-            .with_no_client_auth()
-            .with_single_cert(certs, key)
-            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
-            .expect("Could not build TLS Configuration from Certficiate/Key Combo :");
-        opt_tls_acceptor = Some(TlsAcceptor::from(Arc::new(config)));
+    while let Some((update, result)) = config_updates.recv().await {
+        let applied = running.apply(update.listeners).await;
+        if applied.is_ok() {
+            context.quotas.reconfigure(update.config.quotas.as_ref());
+            *context.rate_limits.write().unwrap() = update.rate_limits;
+            config_tx.send_replace(update.config);
+        }
+        let _ = result.send(applied);
     }
+    future::pending::<()>().await;
+}
 
-    let server: TcpListener = match TcpListener::bind(ws_port).await {
-        Ok(server) => server,
-        Err(err) => {
-            tracing::error!("Unable to start TcpListener {err}");
-            return;
+fn tls_acceptor(
+    tls_config: Option<Arc<rustls::ServerConfig>>,
+    listener: &Listener,
+) -> Option<ReloadableTlsAcceptor> {
+    let acceptor = ReloadableTlsAcceptor::new(tls_config?);
+    if let Some(wss_config) = &listener.secure_websocket {
+        if let Some(secs) = wss_config.reload_interval_secs {
+            acceptor.watch(wss_config.clone(), Duration::from_secs(secs.max(1)));
+        }
+    }
+    Some(acceptor)
+}
+
+// Listen on the Zenoh Session
+async fn run_websocket_server(
+    context: Arc<ServerContext>,
+    listener: PreparedListener,
+    server: Server,
+    mut updates: mpsc::UnboundedReceiver<PreparedListener>,
+) {
+    let bind = listener.config.bind.clone();
+
+    let mut opt_tls_acceptor = tls_acceptor(listener.tls_config, &listener.config);
+    let mut listener_config = Arc::new(listener.config);
+
+    loop {
+        let connection = select!(
+            accepted = server.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    tracing::error!("Unable to accept connection on {bind}: {err}");
+                    break;
+                }
+            },
+            Some(update) = updates.recv() => {
+                opt_tls_acceptor = tls_acceptor(update.tls_config, &update.config);
+                listener_config = Arc::new(update.config);
+                continue;
+            }
+        );
+        // connections being set up keep the acceptor current at the time they were accepted
+        let opt_tls_acceptor = opt_tls_acceptor.as_ref().map(|a| a.current());
+        match connection {
+            Connection::Tcp(stream, sock_addr) => spawn_future(handle_connection(
+                context.clone(),
+                listener_config.clone(),
+                opt_tls_acceptor,
+                stream,
+                sock_addr.to_string(),
+            )),
+            // Unix domain socket peers have no address, the socket path identifies them
+            #[cfg(unix)]
+            Connection::Unix(stream) => spawn_future(handle_connection(
+                context.clone(),
+                listener_config.clone(),
+                opt_tls_acceptor,
+                stream,
+                bind.clone(),
+            )),
+        };
+    }
+}
+
+/// A connection which completed the TLS, HTTP and WebSocket handshakes.
+struct Handshake {
+    ws_stream: WebSocketStream<http::PrefixedStream<Box<dyn Streamable>>>,
+    user: Option<String>,
+    cert_subject: Option<String>,
+    /// The resume query parameter of the upgrade request.
+    resume: Option<String>,
+    /// The application name of the upgrade request.
+    app: Option<String>,
+}
+
+/// Checks the origin and the token of a request to a listener, returns the authenticated user.
+#[allow(clippy::result_large_err)]
+fn check_request(
+    context: &ServerContext,
+    listener: &Listener,
+    config: &Config,
+    request: &Request,
+    sock_addr: &str,
+) -> Result<Option<String>, ErrorResponse> {
+    if let Some(cross_origin) = &config.cross_origin {
+        if let Err(reason) = origins::check_request(cross_origin, request) {
+            tracing::warn!("Rejected client {sock_addr}: {reason}");
+            return Err(origins::forbidden(&reason));
         }
+    }
+    match &context.authenticator {
+        Some(authenticator) => match authenticator.authenticate(request) {
+            Some(user) => Ok(Some(user)),
+            None if listener.auth_required == Some(false) => Ok(None),
+            None => {
+                tracing::warn!("Rejected unauthenticated client {sock_addr}");
+                Err(auth::unauthorized("Invalid or missing token"))
+            }
+        },
+        None => Ok(None),
+    }
+}
+
+/// Carries on the handshakes of a connection, `None` if it was closed or answered
+/// as a plain HTTP request.
+async fn handshake<S: Streamable + 'static>(
+    context: &ServerContext,
+    listener: &Listener,
+    opt_tls_acceptor: Option<TlsAcceptor>,
+    stream: S,
+    sock_addr: &str,
+    config: &Config,
+) -> Option<Handshake> {
+    let mut cert_subject = None;
+    let streamable: Box<dyn Streamable> = match &opt_tls_acceptor {
+        Some(acceptor) => match acceptor.accept(stream).await {
+            Ok(tls_stream) => {
+                cert_subject = tls::peer_cert_subject(tls_stream.get_ref().1);
+                Box::new(tls_stream)
+            }
+            Err(err) => {
+                tracing::error!("Could not secure TcpStream -> TlsStream {:?}", err);
+                context.metrics.register_handshake_failure(&listener.bind);
+                return None;
+            }
+        },
+        None => Box::new(stream),
     };
 
-    while let Ok((tcp_stream, sock_addr)) = server.accept().await {
-        let zenoh_runtime = zenoh_runtime.clone();
-        let opt_tls_acceptor = opt_tls_acceptor.clone();
-        let state_map2 = state_map.clone();
-        let new_websocket = async move {
-            let sock_adress = Arc::new(sock_addr);
-            let (ws_ch_tx, ws_ch_rx) = flume::unbounded::<(OutRemoteMessage, Option<SequenceId>)>();
+    // plain HTTP requests are answered on the same port
+    #[allow(clippy::result_large_err)]
+    let check = |request: &Request| check_request(context, listener, config, request, sock_addr);
+    let streamable =
+        match http::accept(streamable, config.http.as_ref(), &context.metrics, check).await {
+            Ok(Some(streamable)) => streamable,
+            Ok(None) => return None,
+            Err(err) => {
+                tracing::debug!("Invalid HTTP request from {sock_addr}: {err}");
+                context.metrics.register_handshake_failure(&listener.bind);
+                return None;
+            }
+        };
+
+    let mut user = None;
+    let mut resume = None;
+    let mut app = None;
+    // the error response type is imposed by tungstenite
+    #[allow(clippy::result_large_err)]
+    let check_token = |request: &Request, response: Response| {
+        resume = resumption::requested_resumption(request);
+        app = presence::requested_app_name(request);
+        user = check_request(context, listener, config, request, sock_addr)?;
+        Ok(response)
+    };
+    let ws_config = config.message_limits.as_ref().map(websocket_config);
+    let ws_stream =
+        match tokio_tungstenite::accept_hdr_async_with_config(streamable, check_token, ws_config)
+            .await
+        {
+            Ok(ws_stream) => ws_stream,
+            Err(e) => {
+                tracing::error!("Error during the websocket handshake occurred: {}", e);
+                context.metrics.register_handshake_failure(&listener.bind);
+                return None;
+            }
+        };
+    Some(Handshake {
+        ws_stream,
+        user,
+        cert_subject,
+        resume,
+        app,
+    })
+}
 
-            let session = match zenoh::session::init(zenoh_runtime.clone()).await {
+async fn handle_connection<S: Streamable + 'static>(
+    context: Arc<ServerContext>,
+    listener: Arc<Listener>,
+    opt_tls_acceptor: Option<TlsAcceptor>,
+    stream: S,
+    sock_addr: String,
+) {
+    let config = context.config.borrow().clone();
+
+    let connection_limits = config.connection_limits.clone().unwrap_or_default();
+    let message_limits = config.message_limits.clone().unwrap_or_default();
+
+    let handshake = handshake(
+        &context,
+        &listener,
+        opt_tls_acceptor,
+        stream,
+        &sock_addr,
+        &config,
+    );
+    let handshake = match connection_limits.handshake_timeout_secs {
+        Some(secs) => match tokio::time::timeout(Duration::from_secs(secs), handshake).await {
+            Ok(handshake) => handshake,
+            Err(_) => {
+                tracing::warn!("Client {sock_addr} didn't complete its handshake in time");
+                context.metrics.register_handshake_failure(&listener.bind);
+                return;
+            }
+        },
+        None => handshake.await,
+    };
+    let Some(Handshake {
+        ws_stream,
+        user,
+        cert_subject,
+        resume,
+        app,
+    }) = handshake
+    else {
+        return;
+    };
+    let (mut ws_tx, ws_rx) = ws_stream.split();
+
+    // resumption only applies to the clients which opted in to it
+    let resumption = config
+        .session_resumption
+        .as_ref()
+        .filter(|_| resume.is_some());
+    let suspended = match (resumption, resume.as_deref()) {
+        (Some(_), Some(token)) if !token.is_empty() => {
+            context.suspended.resume(token, user.as_deref())
+        }
+        _ => None,
+    };
+    let resumed = suspended.is_some();
+    let mut client = match suspended {
+        Some(mut client) => {
+            tracing::info!("Client {sock_addr} resumed the session of {}", client.id);
+            {
+                let mut admin_client = client.admin_client.lock().unwrap();
+                admin_client.remote_address = sock_addr.clone();
+                admin_client.register_connection();
+            }
+            // the version of the protocol is negotiated again on the new connection
+            client
+                .remote_state
+                .set_protocol_version(LEGACY_PROTOCOL_VERSION);
+            client
+        }
+        None => {
+            let Some(slot) = context.clients.acquire(connection_limits.max_clients) else {
+                tracing::warn!("Rejected client {sock_addr}: too many clients");
+                let close = CloseFrame {
+                    code: CloseCode::Again,
+                    reason: "Too many clients".into(),
+                };
+                if let Err(err) = ws_tx.send(Message::Close(Some(close))).await {
+                    tracing::debug!("WS Send Error: {err:?}");
+                }
+                return;
+            };
+            let session = match zenoh::session::init(context.zenoh_runtime.clone()).await {
                 Ok(session) => session,
                 Err(err) => {
                     tracing::error!("Unable to get Zenoh session from Runtime {err}");
@@ -522,120 +1391,384 @@ async fn run_websocket_server(
                 }
             };
             let id = Uuid::new_v4();
-            tracing::debug!("Client {sock_addr:?} -> {id}");
-
-            let streamable: Box<dyn Streamable> = match &opt_tls_acceptor {
-                Some(acceptor) => match acceptor.accept(tcp_stream).await {
-                    Ok(tls_stream) => Box::new(tls_stream),
-                    Err(err) => {
-                        tracing::error!("Could not secure TcpStream -> TlsStream {:?}", err);
-                        return;
-                    }
-                },
-                None => Box::new(tcp_stream),
-            };
+            tracing::debug!("Client {sock_addr} -> {id}");
 
-            let ws_stream = match tokio_tungstenite::accept_async(streamable).await {
-                Ok(ws_stream) => ws_stream,
-                Err(e) => {
-                    tracing::error!("Error during the websocket handshake occurred: {}", e);
-                    return;
-                }
-            };
-
-            let (ws_tx, ws_rx) = ws_stream.split();
-
-            let ch_rx_stream = ws_ch_rx
-                .into_stream()
-                .map(|(out_msg, sequence_id)| {
-                    tracing::trace!("<< Send: {:?} (seq={:?})", out_msg.id(), sequence_id);
-                    Ok::<_, Box<tokio_tungstenite::tungstenite::Error>>(Message::Binary(
-                        out_msg.to_wire(sequence_id),
-                    ))
-                })
-                .forward(ws_tx.sink_map_err(Box::new));
-
-            // send confirmation that session was successfully opened
-            let admin_client =
-                Arc::new(Mutex::new(AdminSpaceClient::new(id.to_string(), sock_addr)));
-            state_map2
+            let client_acl = match &context.acl {
+                Some(acl) => acl.client_acl(user.as_deref(), cert_subject.as_deref()),
+                None => ClientAcl::allow_all(),
+            }
+            .read_only(listener.read_only);
+            let (commands_tx, commands) = command_channel();
+            let admin_client = Arc::new(Mutex::new(AdminSpaceClient::new(
+                id.to_string(),
+                sock_addr.clone(),
+                listener.bind.clone(),
+                user.clone(),
+                cert_subject,
+                commands_tx,
+            )));
+            let (tx, rx) = outbound_channel(config.outbound_queue.as_ref(), admin_client.clone());
+
+            context
+                .state_map
                 .write()
                 .await
                 .insert(id.to_string(), admin_client.clone());
 
-            let mut remote_state = RemoteState::new(ws_ch_tx.clone(), admin_client, session);
+            let token_key_expr = context.presence.as_ref().map(|presence| {
+                presence.key_expr(&session.zid().into_keyexpr(), &id, app.as_deref())
+            });
+            let mut remote_state = RemoteState::new(
+                tx.clone(),
+                admin_client.clone(),
+                session,
+                client_acl,
+                ClientQuotas::new(context.quotas.clone()),
+                context.rate_limits.read().unwrap().client_limiter(),
+            );
+            if let Some(key_expr) = token_key_expr {
+                remote_state.declare_presence_token(key_expr).await;
+            }
 
-            //  Incoming message from Websocket
-            let incoming_ws = tokio::task::spawn(async move {
-                let mut non_close_messages = ws_rx.try_filter(|msg| future::ready(!msg.is_close()));
+            ClientSession {
+                id,
+                user,
+                remote_state,
+                admin_client,
+                tx,
+                rx: Arc::new(rx),
+                commands,
+                _slot: slot,
+            }
+        }
+    };
+    let id = client.id;
+
+    // a new token is issued on every connection, before the buffered messages are delivered
+    let resume_token = resumption.map(|_| resumption::new_resume_token());
+    if let Some(token) = &resume_token {
+        let msg = OutRemoteMessage::ResumeToken(interface::ResumeToken {
+            token: token.clone(),
+            resumed,
+        });
+        if let Err(err) = ws_tx.send(Message::Binary(msg.to_wire(None))).await {
+            tracing::error!("WS Send Error: {err:?}");
+        }
+    }
 
-                while let Ok(Some(msg)) = non_close_messages.try_next().await {
-                    if let Some(response) = handle_message(msg, &mut remote_state).await {
-                        if let Err(err) = ws_ch_tx.send(response) {
-                            tracing::error!("WS Send Error: {err:?}");
-                        };
-                    };
-                }
-                remote_state.clear().await;
-            });
+    let keepalive = Arc::new(Keepalive::new(
+        connection_limits
+            .idle_timeout_secs
+            .map(|secs| Duration::from_secs(secs.max(1))),
+    ));
 
-            pin_mut!(ch_rx_stream, incoming_ws);
-            future::select(ch_rx_stream, incoming_ws).await;
+    // offered to the client, and used once it negotiated them in its Hello message
+    let compressor = config.compression.as_ref().map(Compressor::new);
+    let batcher = config.batching.as_ref().map(Batcher::new);
 
-            // cleanup state
-            state_map2.write().await.remove(&id.to_string());
+    let mut features = 0;
+    if resume_token.is_some() {
+        features |= protocol::FEATURE_RESUMPTION;
+    }
+    if compressor.is_some() {
+        features |= protocol::FEATURE_COMPRESSION;
+    }
+    if batcher.is_some() {
+        features |= protocol::FEATURE_BATCHING;
+    }
+    let mut negotiation = Negotiation::new(
+        config
+            .min_protocol_version
+            .unwrap_or(LEGACY_PROTOCOL_VERSION),
+        features,
+    );
 
-            tracing::info!("Client Disconnected {}", sock_adress.as_ref());
-        };
+    //  Outgoing messages to Websocket
+    let rx = client.rx.clone();
+    let admin_client = client.admin_client.clone();
+    // the messages of the frame being sent, queued again if it isn't sent
+    let mut in_flight = Vec::new();
+    let outgoing = {
+        let (rx, admin_client, keepalive) = (&rx, &admin_client, &keepalive);
+        let (ws_sink, in_flight) = (&mut ws_tx, &mut in_flight);
+        let (compressor, batcher) = (&compressor, &batcher);
+        async move {
+            // the features negotiated with the client, none until its HelloAck is sent
+            let mut active_features = 0;
+            loop {
+                let (out_msg, sequence_id) = select!(
+                    msg = rx.recv() => msg,
+                    event = keepalive.next() => {
+                        if event == KeepaliveEvent::TimedOut {
+                            tracing::warn!("Client {id} didn't answer the ping, disconnecting");
+                            break;
+                        }
+                        let ping = ws_sink.send(Message::Ping(Default::default()));
+                        match keepalive.bounded(ping).await {
+                            Some(Ok(())) => {}
+                            Some(Err(err)) => {
+                                tracing::debug!("WS Send Error: {err:?}");
+                                break;
+                            }
+                            None => {
+                                tracing::warn!("Client {id} didn't receive the ping, disconnecting");
+                                break;
+                            }
+                        }
+                        continue;
+                    }
+                );
+                tracing::trace!("<< Send: {:?} (seq={:?})", out_msg.id(), sequence_id);
+                let negotiated = match &out_msg {
+                    OutRemoteMessage::HelloAck(ack) => Some(ack.features),
+                    _ => None,
+                };
+                let mut wire = out_msg.to_wire(sequence_id);
+                let mut register_sent = |msg: OutboundMessage, bytes| {
+                    admin_client
+                        .lock()
+                        .unwrap()
+                        .register_sent(msg.0.id(), bytes);
+                    in_flight.push(msg);
+                };
+                register_sent((out_msg, sequence_id), wire.len());
+                let mut sequence_id = sequence_id;
+                let batcher = batcher
+                    .as_ref()
+                    .filter(|_| active_features & protocol::FEATURE_BATCHING != 0);
+                if let Some(batcher) = batcher {
+                    (wire, sequence_id) = batcher.batch(wire, sequence_id, rx, register_sent).await;
+                }
+                let compressor = compressor
+                    .as_ref()
+                    .filter(|_| active_features & protocol::FEATURE_COMPRESSION != 0);
+                if let Some(compressor) = compressor {
+                    wire = compressor.compress(wire, sequence_id);
+                }
+                match keepalive.bounded(ws_sink.send(Message::Binary(wire))).await {
+                    Some(Ok(())) => {
+                        in_flight.clear();
+                        // the HelloAck itself is sent before the features it enables
+                        if let Some(features) = negotiated {
+                            active_features = features;
+                        }
+                    }
+                    Some(Err(err)) => {
+                        tracing::debug!("WS Send Error: {err:?}");
+                        break;
+                    }
+                    None => {
+                        tracing::warn!("Client {id} didn't receive its messages, disconnecting");
+                        break;
+                    }
+                }
+            }
+        }
+    };
+
+    //  Incoming messages from Websocket, in their own task for the messages delivered
+    //  synchronously to the client while it waits for room in its queue to be sent
+    let stop_incoming = Arc::new(Notify::new());
+    let mut incoming = tokio::task::spawn({
+        let stop = stop_incoming.clone();
+        let keepalive = keepalive.clone();
+        async move {
+            let tx = &client.tx;
+            let admin_client = &client.admin_client;
+            let remote_state = &mut client.remote_state;
+            let commands = &mut client.commands;
+            let mut non_close_messages = ws_rx.try_filter(|msg| future::ready(!msg.is_close()));
+
+            // stops between two messages, not to leave one half handled
+            let close = loop {
+                let msg = select!(
+                    msg = non_close_messages.try_next() => msg,
+                    _ = stop.notified() => break None,
+                    _ = tx.overflow_disconnect() => {
+                        tracing::warn!("Client {id} exceeded its outbound queue, disconnecting");
+                        break None;
+                    }
+                    command = commands.recv() => match command {
+                        AdminCommand::Disconnect => {
+                            tracing::warn!("Client {id} was disconnected by the administrator");
+                            break Some(CloseFrame {
+                                code: CloseCode::Policy,
+                                reason: "Disconnected by the administrator".into(),
+                            });
+                        }
+                        AdminCommand::Revoke(entity) => {
+                            let error = match remote_state.revoke(entity).await {
+                                Ok(()) => {
+                                    tracing::warn!("{entity} of client {id} was revoked by the administrator");
+                                    format!("{entity} was revoked by the administrator")
+                                }
+                                Err(err) => {
+                                    tracing::warn!("Failed to revoke {entity} of client {id}: {err}");
+                                    continue;
+                                }
+                            };
+                            let error = OutRemoteMessage::Error(interface::Error { error });
+                            if let Err(err) = tx.send_response((error, None)) {
+                                tracing::error!("WS Send Error: {err:?}");
+                            }
+                            continue;
+                        }
+                    }
+                );
+                let msg = match msg {
+                    Ok(Some(msg)) => msg,
+                    Ok(None) => break None,
+                    Err(tungstenite::Error::Capacity(err)) => {
+                        tracing::warn!(
+                            "Client {id} exceeded the message limits, disconnecting: {err}"
+                        );
+                        admin_client.lock().unwrap().register_oversized_message();
+                        break None;
+                    }
+                    Err(_) => break None,
+                };
+                keepalive.seen();
+                // answered by tungstenite, or answering the pings of the keepalive
+                if msg.is_ping() || msg.is_pong() {
+                    continue;
+                }
+                let response = handle_message(
+                    msg,
+                    remote_state,
+                    &message_limits,
+                    admin_client,
+                    &mut negotiation,
+                )
+                .await;
+                if let Some(response) = response {
+                    if let Err(err) = tx.send_response(response) {
+                        tracing::error!("WS Send Error: {err:?}");
+                    };
+                };
+                if let Some(rejection) = negotiation.rejection() {
+                    tracing::warn!("Rejected client {id}: {rejection}");
+                    break Some(CloseFrame {
+                        code: CloseCode::Protocol,
+                        reason: rejection.into(),
+                    });
+                }
+            };
+            (client, close)
+        }
+    });
+
+    let joined = select!(
+        _ = outgoing => {
+            // nothing sends the messages anymore, the callbacks must not wait for room
+            rx.suspend();
+            stop_incoming.notify_one();
+            (&mut incoming).await
+        }
+        joined = &mut incoming => joined,
+    );
+    rx.requeue(std::mem::take(&mut in_flight));
+    let (mut client, close) = match joined {
+        Ok(joined) => joined,
+        Err(err) => {
+            tracing::error!("The connection of client {id} failed: {err}");
+            let mut state_map = context.state_map.write().await;
+            state_map.remove(&id.to_string());
+            context
+                .metrics
+                .register_session_end(&admin_client.lock().unwrap());
+            return;
+        }
+    };
+    let closed = close.is_some();
+    if let Some(close) = close {
+        let close = ws_tx.send(Message::Close(Some(close)));
+        if let Some(Err(err)) = keepalive.bounded(close).await {
+            tracing::debug!("WS Send Error: {err:?}");
+        }
+    }
+    tracing::info!("Client Disconnected {}", sock_addr);
+
+    // a client which overflowed its outbound queue lost messages, its session can't be resumed,
+    // neither can the session of a client rejected or disconnected by the administrator
+    if let (Some(resumption), Some(token)) = (resumption, resume_token) {
+        if !client.tx.is_closed() && !closed {
+            let grace_period = Duration::from_secs(resumption.grace_period_secs);
+            tracing::info!("Keeping the session of client {id} for {grace_period:?}");
+            let tx = client.tx.clone();
+            let disconnection = client.commands.disconnection();
+            context.suspended.suspend(token.clone(), client);
+            select!(
+                _ = tokio::time::sleep(grace_period) => {},
+                _ = tx.overflow_disconnect() => {
+                    tracing::warn!("Client {id} exceeded its outbound queue while disconnected");
+                },
+                _ = disconnection.notified() => {
+                    tracing::warn!("Client {id} was disconnected by the administrator");
+                }
+            );
+            match context.suspended.expire(&token) {
+                Some(expired) => {
+                    tracing::info!("The session of client {id} expired");
+                    client = expired;
+                }
+                // resumed by a new connection
+                None => return,
+            }
+        }
+    }
 
-        spawn_future(new_websocket);
+    // cleanup state
+    client.remote_state.clear().await;
+    let mut state_map = context.state_map.write().await;
+    state_map.remove(&id.to_string());
+    // while the map is locked, for the metrics to count the client once
+    context
+        .metrics
+        .register_session_end(&client.admin_client.lock().unwrap());
+}
+
+/// The settings of the WebSocket connections, with tungstenite's defaults for the limits not set.
+fn websocket_config(limits: &MessageLimits) -> WebSocketConfig {
+    let mut config = WebSocketConfig::default();
+    if limits.max_frame_size.is_some() {
+        config = config.max_frame_size(limits.max_frame_size);
+    }
+    if limits.max_message_size.is_some() {
+        config = config.max_message_size(limits.max_message_size);
     }
+    config
 }
 
 async fn handle_message(
     msg: Message,
     state: &mut RemoteState,
+    limits: &MessageLimits,
+    admin_client: &Mutex<AdminSpaceClient>,
+    negotiation: &mut Negotiation,
 ) -> Option<(OutRemoteMessage, Option<SequenceId>)> {
-    match msg {
-        Message::Binary(val) => match InRemoteMessage::from_wire(val) {
-            Ok((header, msg)) => {
-                tracing::trace!(
-                    ">> Recv: {:?} (seq={:?})",
-                    header.content_id,
-                    header.sequence_id
-                );
-                match state.handle_message(msg).await {
-                    Ok(Some(msg)) => Some((msg, header.sequence_id)),
-                    Ok(None) => header.sequence_id.map(|_| {
-                        (
-                            OutRemoteMessage::Ok(interface::Ok {
-                                content_id: header.content_id,
-                            }),
-                            header.sequence_id,
-                        )
-                    }),
-                    Err(error) => {
-                        tracing::error!(
-                            "RemoteAPI: Failed to execute request {:?}: {}",
-                            header.content_id,
-                            error
-                        );
-                        header.sequence_id.map(|_| {
-                            // send error response if ack was requested
-                            (
-                                OutRemoteMessage::Error(interface::Error {
-                                    error: error.to_string(),
-                                }),
-                                header.sequence_id,
-                            )
-                        })
-                    }
-                }
-            }
-            Err(err) => match err {
+    let Message::Binary(val) = msg else {
+        tracing::error!("RemoteAPI: message format is not `Binary`");
+        admin_client.lock().unwrap().register_error();
+        return None;
+    };
+    let bytes = val.len();
+    let (header, msg) = match InRemoteMessage::from_wire_limited(val, limits) {
+        Ok(msg) => msg,
+        Err(err) => {
+            let (header, error) = match err {
                 interface::FromWireError::HeaderError(error) => {
                     tracing::error!("RemoteAPI: Failed to parse message header: {}", error);
-                    None
+                    admin_client.lock().unwrap().register_error();
+                    return None;
+                }
+                interface::FromWireError::FieldTooLarge((header, error)) => {
+                    tracing::warn!(
+                        "RemoteAPI: Rejected message {:?}: {}",
+                        header.content_id,
+                        error
+                    );
+                    admin_client.lock().unwrap().register_oversized_message();
+                    (header, error)
                 }
                 interface::FromWireError::BodyError((header, error)) => {
                     tracing::error!(
@@ -643,6 +1776,69 @@ async fn handle_message(
                         header,
                         error
                     );
+                    (header, error)
+                }
+            };
+            let mut admin_client = admin_client.lock().unwrap();
+            admin_client.register_received(header.content_id, bytes);
+            admin_client.register_error();
+            return header.sequence_id.map(|_| {
+                // send error response if ack was requested
+                (
+                    OutRemoteMessage::Error(interface::Error {
+                        error: error.to_string(),
+                    }),
+                    header.sequence_id,
+                )
+            });
+        }
+    };
+    admin_client
+        .lock()
+        .unwrap()
+        .register_received(header.content_id, bytes);
+    match msg {
+        InRemoteMessage::Hello(hello) => {
+            let ack = negotiation.hello(&hello)?;
+            tracing::debug!(
+                "Client {}: protocol version {}, features {:#b}",
+                admin_client.lock().unwrap().id(),
+                ack.version,
+                ack.features
+            );
+            state.set_protocol_version(ack.version);
+            Some((OutRemoteMessage::HelloAck(ack), header.sequence_id))
+        }
+        msg => {
+            negotiation.check()?;
+            tracing::trace!(
+                ">> Recv: {:?} (seq={:?})",
+                header.content_id,
+                header.sequence_id
+            );
+            match state.handle_message(msg).await {
+                Ok(Some(msg)) => {
+                    // denied by the access control, the quotas or the rate limits
+                    if let OutRemoteMessage::Error(_) = msg {
+                        admin_client.lock().unwrap().register_error();
+                    }
+                    Some((msg, header.sequence_id))
+                }
+                Ok(None) => header.sequence_id.map(|_| {
+                    (
+                        OutRemoteMessage::Ok(interface::Ok {
+                            content_id: header.content_id,
+                        }),
+                        header.sequence_id,
+                    )
+                }),
+                Err(error) => {
+                    tracing::error!(
+                        "RemoteAPI: Failed to execute request {:?}: {}",
+                        header.content_id,
+                        error
+                    );
+                    admin_client.lock().unwrap().register_error();
                     header.sequence_id.map(|_| {
                         // send error response if ack was requested
                         (
@@ -653,11 +1849,236 @@ async fn handle_message(
                         )
                     })
                 }
-            },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tokio_tungstenite::tungstenite::Message;
+    use zenoh_ext::ZSerializer;
+
+    use super::{handle_message, Listeners};
+    use crate::{
+        acl::ClientAcl,
+        commands::command_channel,
+        config::{Config, MessageLimits},
+        interface::{
+            InRemoteMessageId, LinkEventsListenerId, LivelinessTokenId, MatchingListenerId,
+            OutRemoteMessage, PublisherId, QuerierId, QueryId, QueryableId, SubscriberId,
+            TransportEventsListenerId,
         },
-        _ => {
-            tracing::error!("RemoteAPI: message format is not `Binary`");
-            None
+        listeners::{prepare_listeners, PreparedListener},
+        outbound::outbound_channel,
+        protocol::{Negotiation, LEGACY_PROTOCOL_VERSION},
+        quotas::{ClientQuotas, GlobalQuotas},
+        ratelimit::RateLimits,
+        remote_state::RemoteState,
+        AdminSpaceClient, AdminSpaceEntity,
+    };
+
+    fn put(payload: usize, sequence_id: u32) -> Message {
+        let mut serializer = ZSerializer::new();
+        serializer.serialize(u8::from(InRemoteMessageId::Put) | 0b10000000u8);
+        serializer.serialize(sequence_id);
+        serializer.serialize("demo/put".to_string());
+        serializer.serialize(vec![0u8; payload]);
+        serializer.serialize((0u16, String::new()));
+        serializer.serialize(false);
+        serializer.serialize(false);
+        serializer.serialize(0u8);
+        Message::Binary(serializer.finish().to_bytes().to_vec().into())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_oversized_message() {
+        let mut config = zenoh::Config::default();
+        config
+            .insert_json5("scouting/multicast/enabled", "false")
+            .unwrap();
+        config.insert_json5("listen/endpoints", "[]").unwrap();
+        let session = zenoh::open(config).await.unwrap();
+        let admin_client = Arc::new(Mutex::new(AdminSpaceClient::new(
+            "a".to_string(),
+            "127.0.0.1:50000".to_string(),
+            "[::]:10000".to_string(),
+            None,
+            None,
+            command_channel().0,
+        )));
+        let (tx, _rx) = outbound_channel(None, admin_client.clone());
+        let mut state = RemoteState::new(
+            tx,
+            admin_client.clone(),
+            session,
+            ClientAcl::allow_all(),
+            ClientQuotas::new(Arc::new(GlobalQuotas::new(None))),
+            RateLimits::new(&[]).unwrap().client_limiter(),
+        );
+        let mut negotiation = Negotiation::new(LEGACY_PROTOCOL_VERSION, 0);
+        let limits = MessageLimits {
+            max_payload_size: Some(10),
+            ..Default::default()
+        };
+
+        let response = handle_message(
+            put(11, 5),
+            &mut state,
+            &limits,
+            &admin_client,
+            &mut negotiation,
+        )
+        .await;
+        assert!(matches!(
+            response,
+            Some((OutRemoteMessage::Error(_), Some(5)))
+        ));
+        assert_eq!(admin_client.lock().unwrap().oversized_messages, 1);
+        assert_eq!(admin_client.lock().unwrap().stats().errors(), 1);
+
+        let response = handle_message(
+            put(10, 6),
+            &mut state,
+            &limits,
+            &admin_client,
+            &mut negotiation,
+        )
+        .await;
+        assert!(matches!(response, Some((OutRemoteMessage::Ok(_), Some(6)))));
+        assert_eq!(admin_client.lock().unwrap().oversized_messages, 1);
+    }
+
+    #[test]
+    fn test_admin_space_client_entities() {
+        let mut client = AdminSpaceClient::new(
+            "a".to_string(),
+            "127.0.0.1:50000".to_string(),
+            "[::]:10000".to_string(),
+            None,
+            None,
+            command_channel().0,
+        );
+        let entity = || AdminSpaceEntity::new(Some("demo/a")).setting("id", 1);
+        client.register_publisher(PublisherId(1), entity());
+        client.register_subscriber(SubscriberId(1), entity());
+        client.register_queryable(QueryableId(1), entity());
+        client.register_querier(QuerierId(1), entity());
+        client.register_liveliness_token(LivelinessTokenId(1), entity());
+        client.register_liveliness_subscriber(SubscriberId(1), entity());
+        client.register_matching_listener(MatchingListenerId(1), entity());
+        client.register_transport_events_listener(TransportEventsListenerId(1), entity());
+        client.register_link_events_listener(LinkEventsListenerId(1), entity());
+        client.register_pending_query(QueryId(1), entity());
+
+        let kinds = [
+            "publishers",
+            "subscribers",
+            "queryables",
+            "queriers",
+            "liveliness_tokens",
+            "liveliness_subscribers",
+            "matching_listeners",
+            "transport_events_listeners",
+            "link_events_listeners",
+            "pending_queries",
+        ];
+        let json = serde_json::to_value(&client).unwrap();
+        for kind in kinds {
+            assert_eq!(json[kind]["1"]["key_expr"], "demo/a", "{kind}");
+            assert_eq!(json[kind]["1"]["settings"]["id"], 1, "{kind}");
+        }
+
+        client.unregister_publisher(PublisherId(1));
+        client.unregister_subscriber(SubscriberId(1));
+        client.unregister_queryable(QueryableId(1));
+        client.unregister_querier(QuerierId(1));
+        client.unregister_liveliness_token(LivelinessTokenId(1));
+        client.unregister_liveliness_subscriber(SubscriberId(1));
+        client.unregister_matching_listener(MatchingListenerId(1));
+        client.unregister_transport_events_listener(TransportEventsListenerId(1));
+        client.unregister_link_events_listener(LinkEventsListenerId(1));
+        client.unregister_pending_query(QueryId(1));
+        let json = serde_json::to_value(&client).unwrap();
+        for kind in kinds {
+            assert_eq!(json[kind], serde_json::json!({}), "{kind}");
+        }
+    }
+
+    fn listeners(binds: &[&str]) -> Vec<PreparedListener> {
+        let listeners = binds
+            .iter()
+            .map(|bind| serde_json::json!({ "bind": bind }))
+            .collect::<Vec<_>>();
+        let config: Config =
+            serde_json::from_value(serde_json::json!({ "listeners": listeners })).unwrap();
+        prepare_listeners(&config).unwrap()
+    }
+
+    fn running() -> Listeners {
+        Listeners::new(Box::new(|_, server, _| {
+            tokio::spawn(async move {
+                let _server = server;
+                std::future::pending::<()>().await
+            })
+        }))
+    }
+
+    fn free_port() -> u16 {
+        std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    fn is_bound(addr: &str) -> bool {
+        std::net::TcpListener::bind(addr).is_err()
+    }
+
+    #[tokio::test]
+    async fn test_listeners_move_port() {
+        let port = free_port();
+        let local = format!("127.0.0.1:{port}");
+        let any = format!("0.0.0.0:{port}");
+        let mut running = running();
+        running.apply(listeners(&[&local])).await.unwrap();
+        assert!(is_bound(&local));
+
+        // the listener being removed releases the port for the new one
+        running.apply(listeners(&[&any])).await.unwrap();
+        assert_eq!(running.running.keys().collect::<Vec<_>>(), [&any]);
+        assert!(is_bound(&local));
+
+        running.apply(Vec::new()).await.unwrap();
+        assert!(running.running.is_empty());
+        assert!(!is_bound(&local));
+    }
+
+    #[tokio::test]
+    async fn test_listeners_bind_failure() {
+        let in_use = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let in_use = in_use.local_addr().unwrap().to_string();
+        let local = format!("127.0.0.1:{}", free_port());
+        let mut running = running();
+        running.apply(listeners(&[&local])).await.unwrap();
+
+        // the running listener is kept when the new one can't be bound
+        assert!(running.apply(listeners(&[&in_use])).await.is_err());
+        assert_eq!(running.running.keys().collect::<Vec<_>>(), [&local]);
+        assert!(!running.running[&local].task.is_finished());
+        assert!(is_bound(&local));
+
+        // a server which stopped is started again
+        running.running.get_mut(&local).unwrap().task.abort();
+        tokio::task::yield_now().await;
+        while !running.running[&local].task.is_finished() {
+            tokio::task::yield_now().await;
         }
+        running.apply(listeners(&[&local])).await.unwrap();
+        assert!(!running.running[&local].task.is_finished());
+        assert!(is_bound(&local));
     }
 }
@@ -0,0 +1,281 @@
+//
+// Copyright (c) 2026 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{collections::HashSet, io, net::SocketAddr, sync::Arc};
+
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::rustls::ServerConfig;
+use zenoh_result::{bail, zerror, ZResult};
+
+use crate::{
+    config::{Config, Listener, UNIX_SOCKET_PREFIX},
+    tls,
+};
+
+/// A socket bound to the address of a listener.
+pub enum BoundSocket {
+    Tcp(std::net::TcpListener),
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixListener, unix::SocketFile),
+}
+
+/// A listener of the configuration, with the resources it requires loaded.
+pub struct PreparedListener {
+    pub(crate) config: Listener,
+    /// The socket bound to the listener address, `None` until the listener is started,
+    /// or if the address is already bound by a running listener.
+    pub(crate) socket: Option<BoundSocket>,
+    pub(crate) tls_config: Option<Arc<ServerConfig>>,
+}
+
+impl PreparedListener {
+    /// Binds the listener address ahead of starting it, so that failing to bind can be reported.
+    pub(crate) fn bind(&mut self) -> ZResult<()> {
+        self.socket = Some(bind_socket(&self.config)?);
+        Ok(())
+    }
+}
+
+/// Checks the socket settings of a listener, which are only applied when binding its address.
+fn check_socket(listener: &Listener) -> ZResult<()> {
+    match listener.bind.strip_prefix(UNIX_SOCKET_PREFIX) {
+        #[cfg(unix)]
+        Some(_) => {
+            if let Some(mode) = &listener.socket_mode {
+                unix::parse_mode(mode)?;
+            }
+            Ok(())
+        }
+        #[cfg(not(unix))]
+        Some(_) => bail!("Unix domain sockets are not supported on this platform"),
+        None if listener.socket_mode.is_some() => {
+            bail!("`socket_mode` only applies to Unix domain socket listeners")
+        }
+        None => Ok(()),
+    }
+}
+
+fn bind_socket(listener: &Listener) -> ZResult<BoundSocket> {
+    match listener.bind.strip_prefix(UNIX_SOCKET_PREFIX) {
+        #[cfg(unix)]
+        Some(path) => unix::bind(path, listener.socket_mode.as_deref()),
+        #[cfg(not(unix))]
+        Some(_) => bail!("Unix domain sockets are not supported on this platform"),
+        None => {
+            if listener.socket_mode.is_some() {
+                bail!("`socket_mode` only applies to Unix domain socket listeners");
+            }
+            let socket = std::net::TcpListener::bind(&listener.bind)
+                .and_then(|socket| socket.set_nonblocking(true).map(|_| socket))
+                .map_err(|e| zerror!("Unable to bind {}: {e}", listener.bind))?;
+            Ok(BoundSocket::Tcp(socket))
+        }
+    }
+}
+
+/// Checks the listeners of a configuration and loads their TLS configuration.
+/// Their addresses are not bound, which is left to starting them.
+pub(crate) fn prepare_listeners(config: &Config) -> ZResult<Vec<PreparedListener>> {
+    let mut binds = HashSet::new();
+    let mut listeners = Vec::new();
+    for listener in config.listeners() {
+        if !binds.insert(listener.bind.clone()) {
+            bail!("Duplicate listener on {}", listener.bind);
+        }
+        if listener.auth_required == Some(true) && config.authentication.is_none() {
+            bail!(
+                "Listener on {} requires authentication, but `authentication` is not configured",
+                listener.bind
+            );
+        }
+        check_socket(&listener)?;
+        let tls_config = match &listener.secure_websocket {
+            Some(wss_config) => Some(tls::load_server_config(wss_config)?),
+            None => None,
+        };
+        listeners.push(PreparedListener {
+            config: listener,
+            socket: None,
+            tls_config,
+        });
+    }
+    Ok(listeners)
+}
+
+/// A connection accepted by a listener.
+pub(crate) enum Connection {
+    Tcp(TcpStream, SocketAddr),
+    #[cfg(unix)]
+    Unix(tokio::net::UnixStream),
+}
+
+/// The socket on which a WebSocket server accepts connections.
+pub(crate) enum Server {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix {
+        listener: tokio::net::UnixListener,
+        // removes the socket file when the server stops
+        _file: unix::SocketFile,
+    },
+}
+
+impl Server {
+    /// Registers the socket of the listener to the runtime, binding it if not done already.
+    pub(crate) fn new(listener: &Listener, socket: Option<BoundSocket>) -> ZResult<Self> {
+        let socket = match socket {
+            Some(socket) => socket,
+            None => bind_socket(listener)?,
+        };
+        let server = match socket {
+            BoundSocket::Tcp(socket) => Server::Tcp(TcpListener::from_std(socket)?),
+            #[cfg(unix)]
+            BoundSocket::Unix(socket, file) => Server::Unix {
+                listener: tokio::net::UnixListener::from_std(socket)?,
+                _file: file,
+            },
+        };
+        Ok(server)
+    }
+
+    pub(crate) async fn accept(&self) -> io::Result<Connection> {
+        match self {
+            Server::Tcp(listener) => {
+                let (stream, addr) = listener.accept().await?;
+                Ok(Connection::Tcp(stream, addr))
+            }
+            #[cfg(unix)]
+            Server::Unix { listener, .. } => {
+                let (stream, _) = listener.accept().await?;
+                Ok(Connection::Unix(stream))
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::{
+        fs,
+        io::{self, ErrorKind},
+        os::unix::{
+            fs::{DirBuilderExt, FileTypeExt, PermissionsExt},
+            net::{UnixListener, UnixStream},
+        },
+        path::PathBuf,
+    };
+
+    use zenoh_result::{bail, zerror, ZResult};
+
+    use super::BoundSocket;
+
+    /// The file of a bound Unix socket, removed when the listener is closed.
+    pub struct SocketFile(PathBuf);
+
+    impl Drop for SocketFile {
+        fn drop(&mut self) {
+            if let Err(e) = fs::remove_file(&self.0) {
+                tracing::warn!("Unable to remove Unix socket {:?}: {e}", self.0);
+            }
+        }
+    }
+
+    /// Removes the socket file left by a process which didn't exit cleanly.
+    /// A socket still accepting connections, or any other kind of file, is left untouched.
+    fn remove_stale_socket(path: &str) -> ZResult<()> {
+        let metadata = match fs::symlink_metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => bail!("Unable to access {path}: {e}"),
+        };
+        if !metadata.file_type().is_socket() {
+            bail!("Unable to bind unix:{path}: the file exists and is not a socket");
+        }
+        match UnixStream::connect(path) {
+            Ok(_) => bail!("Unable to bind unix:{path}: the socket is in use"),
+            Err(e) if e.kind() == ErrorKind::ConnectionRefused => {
+                tracing::info!("Removing stale Unix socket {path}");
+                fs::remove_file(path).map_err(|e| zerror!("Unable to remove {path}: {e}"))?;
+                Ok(())
+            }
+            Err(e) => bail!("Unable to bind unix:{path}: {e}"),
+        }
+    }
+
+    pub(super) fn parse_mode(mode: &str) -> ZResult<u32> {
+        Ok(u32::from_str_radix(mode, 8)
+            .map_err(|_| zerror!("Invalid socket mode '{mode}', expected an octal number"))?)
+    }
+
+    /// Binds the socket in a directory only accessible to the current user, where it is given
+    /// its mode before being moved to its path, so that no one can connect to it in between.
+    fn bind_with_mode(path: &str, mode: u32) -> io::Result<UnixListener> {
+        let dir = PathBuf::from(format!("{path}.{}", std::process::id()));
+        fs::DirBuilder::new().mode(0o700).create(&dir)?;
+        let tmp = dir.join("s");
+        let socket = UnixListener::bind(&tmp).and_then(|socket| {
+            fs::set_permissions(&tmp, fs::Permissions::from_mode(mode))?;
+            fs::rename(&tmp, path)?;
+            Ok(socket)
+        });
+        if socket.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+        fs::remove_dir(&dir)?;
+        socket
+    }
+
+    pub(super) fn bind(path: &str, mode: Option<&str>) -> ZResult<BoundSocket> {
+        let mode = mode.map(parse_mode).transpose()?;
+        remove_stale_socket(path)?;
+        let socket = match mode {
+            Some(mode) => bind_with_mode(path, mode),
+            None => UnixListener::bind(path),
+        };
+        let socket = socket
+            .and_then(|socket| socket.set_nonblocking(true).map(|_| socket))
+            .map_err(|e| zerror!("Unable to bind unix:{path}: {e}"))?;
+        Ok(BoundSocket::Unix(socket, SocketFile(PathBuf::from(path))))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    use super::{unix, BoundSocket};
+
+    #[test]
+    fn test_unix_socket() {
+        let path = std::env::temp_dir().join(format!("remote-api-{}.sock", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        // a socket left by a process which didn't exit cleanly is replaced
+        drop(std::os::unix::net::UnixListener::bind(path).unwrap());
+        let socket = unix::bind(path, Some("600")).unwrap();
+        let mode = fs::metadata(path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        // the directory it was bound in is removed
+        assert!(fs::metadata(format!("{path}.{}", std::process::id())).is_err());
+        std::os::unix::net::UnixStream::connect(path).unwrap();
+
+        // a socket in use is not
+        assert!(unix::bind(path, None).is_err());
+
+        drop(socket);
+        assert!(fs::metadata(path).is_err());
+        assert!(unix::bind(path, Some("rw")).is_err());
+        assert!(unix::bind(path, None).is_ok_and(|s| matches!(s, BoundSocket::Unix(..))));
+    }
+}
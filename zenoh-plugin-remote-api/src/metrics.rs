@@ -0,0 +1,367 @@
+//
+// Copyright (c) 2026 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{collections::BTreeMap, fmt::Write, sync::Mutex};
+
+use crate::{
+    stats::{ClientStats, Traffic},
+    AdminSpaceClient, StateMap,
+};
+
+const PREFIX: &str = "zenoh_remote_api_";
+
+type TrafficByType = fn(&ClientStats) -> &BTreeMap<&'static str, Traffic>;
+
+/// The content type of the Prometheus text format.
+pub(crate) const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// The metrics of the plugin, computed from the clients in the admin space when exported.
+pub(crate) struct ServerMetrics {
+    state_map: StateMap,
+    /// The traffic of the clients whose session ended, by listener,
+    /// so that the counters of a listener never decrease.
+    ended: Mutex<BTreeMap<String, ClientStats>>,
+    handshake_failures: Mutex<BTreeMap<String, u64>>,
+}
+
+/// The metrics of a listener, or of a client.
+struct Series {
+    labels: Vec<(&'static str, String)>,
+    stats: ClientStats,
+    outbound_queue_depth: usize,
+    pending_queries: usize,
+}
+
+impl ServerMetrics {
+    pub(crate) fn new(state_map: StateMap) -> Self {
+        ServerMetrics {
+            state_map,
+            ended: Mutex::new(BTreeMap::new()),
+            handshake_failures: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub(crate) fn register_handshake_failure(&self, listener: &str) {
+        *self
+            .handshake_failures
+            .lock()
+            .unwrap()
+            .entry(listener.to_string())
+            .or_default() += 1;
+    }
+
+    /// Keeps the traffic of a client whose session ended in the totals of its listener,
+    /// when it is removed from the admin space.
+    pub(crate) fn register_session_end(&self, client: &AdminSpaceClient) {
+        self.ended
+            .lock()
+            .unwrap()
+            .entry(client.listener().to_string())
+            .or_insert_with(ClientStats::new)
+            .merge(client.stats());
+    }
+
+    /// Exports the metrics in the Prometheus text format.
+    pub(crate) async fn export(&self, per_client: bool) -> String {
+        let mut clients = BTreeMap::<String, u64>::new();
+        let mut series = Vec::new();
+        // the clients are moved to the ended sessions while the map is locked
+        let state_map = self.state_map.read().await;
+        for client in state_map.values() {
+            let client = client.lock().unwrap();
+            *clients.entry(client.listener().to_string()).or_default() += 1;
+            series.push(Series {
+                labels: vec![
+                    ("listener", client.listener().to_string()),
+                    ("client", client.id().to_string()),
+                ],
+                stats: client.stats().clone(),
+                outbound_queue_depth: client.outbound_queue_depth(),
+                pending_queries: client.pending_query_count(),
+            });
+        }
+        if !per_client {
+            series = by_listener(series, &self.ended.lock().unwrap());
+        }
+        drop(state_map);
+        let handshake_failures = self.handshake_failures.lock().unwrap().clone();
+
+        let mut out = Exposition::default();
+        out.family(
+            "clients",
+            "gauge",
+            "The number of clients, including the suspended sessions.",
+        );
+        for (listener, count) in &clients {
+            out.sample("clients", &[("listener", listener.as_str())], *count);
+        }
+        out.family(
+            "handshake_failures_total",
+            "counter",
+            "The number of connections which failed to complete their handshakes.",
+        );
+        for (listener, count) in &handshake_failures {
+            out.sample(
+                "handshake_failures_total",
+                &[("listener", listener.as_str())],
+                *count,
+            );
+        }
+        let all = |_: &str| true;
+        out.traffic(
+            &series,
+            "received_messages_total",
+            "The number of messages received from the clients, by type.",
+            ClientStats::received_traffic,
+            |t| t.messages,
+            all,
+        );
+        out.traffic(
+            &series,
+            "received_bytes_total",
+            "The size of the messages received from the clients, by type.",
+            ClientStats::received_traffic,
+            |t| t.bytes,
+            all,
+        );
+        out.traffic(
+            &series,
+            "sent_messages_total",
+            "The number of messages sent to the clients, by type.",
+            ClientStats::sent_traffic,
+            |t| t.messages,
+            all,
+        );
+        out.traffic(
+            &series,
+            "sent_bytes_total",
+            "The size of the messages sent to the clients before their compression, by type.",
+            ClientStats::sent_traffic,
+            |t| t.bytes,
+            all,
+        );
+        out.traffic(
+            &series,
+            "declarations_total",
+            "The number of declarations requested by the clients, by message type.",
+            ClientStats::received_traffic,
+            |t| t.messages,
+            |kind| kind.contains("Declare") && !kind.starts_with("Undeclare"),
+        );
+        out.traffic(
+            &series,
+            "undeclarations_total",
+            "The number of undeclarations requested by the clients, by message type.",
+            ClientStats::received_traffic,
+            |t| t.messages,
+            |kind| kind.starts_with("Undeclare"),
+        );
+        out.family(
+            "errors_total",
+            "counter",
+            "The number of messages from the clients which couldn't be parsed or executed.",
+        );
+        for s in &series {
+            out.sample("errors_total", &s.labels(), s.stats.errors());
+        }
+        out.family(
+            "outbound_queue_depth",
+            "gauge",
+            "The number of messages waiting to be sent to the clients.",
+        );
+        for s in &series {
+            out.sample(
+                "outbound_queue_depth",
+                &s.labels(),
+                s.outbound_queue_depth as u64,
+            );
+        }
+        out.family(
+            "pending_queries",
+            "gauge",
+            "The number of queries received by the queryables of the clients, not answered yet.",
+        );
+        for s in &series {
+            out.sample("pending_queries", &s.labels(), s.pending_queries as u64);
+        }
+        out.text
+    }
+}
+
+impl Series {
+    fn labels(&self) -> Vec<(&str, &str)> {
+        self.labels
+            .iter()
+            .map(|(name, value)| (*name, value.as_str()))
+            .collect()
+    }
+
+    fn labels_with<'a>(&'a self, name: &'a str, value: &'a str) -> Vec<(&'a str, &'a str)> {
+        let mut labels = self.labels();
+        labels.push((name, value));
+        labels
+    }
+}
+
+/// Totals the series of the clients by listener, with the traffic of the ended sessions.
+fn by_listener(clients: Vec<Series>, ended: &BTreeMap<String, ClientStats>) -> Vec<Series> {
+    let mut listeners = BTreeMap::<String, Series>::new();
+    let listener_series = |listener: &str| Series {
+        labels: vec![("listener", listener.to_string())],
+        stats: ClientStats::new(),
+        outbound_queue_depth: 0,
+        pending_queries: 0,
+    };
+    for (listener, stats) in ended {
+        listeners
+            .entry(listener.clone())
+            .or_insert_with(|| listener_series(listener))
+            .stats
+            .merge(stats);
+    }
+    for client in clients {
+        let listener = client.labels[0].1.clone();
+        let series = listeners
+            .entry(listener.clone())
+            .or_insert_with(|| listener_series(&listener));
+        series.stats.merge(&client.stats);
+        series.outbound_queue_depth += client.outbound_queue_depth;
+        series.pending_queries += client.pending_queries;
+    }
+    listeners.into_values().collect()
+}
+
+/// Writes metrics in the Prometheus text format.
+#[derive(Default)]
+struct Exposition {
+    text: String,
+}
+
+impl Exposition {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.text, "# HELP {PREFIX}{name} {help}");
+        let _ = writeln!(self.text, "# TYPE {PREFIX}{name} {kind}");
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: u64) {
+        let labels = labels
+            .iter()
+            .map(|(name, value)| format!("{name}=\"{}\"", escape(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+        let _ = writeln!(self.text, "{PREFIX}{name}{{{labels}}} {value}");
+    }
+
+    /// Writes a counter of the messages of the types selected by `filter`, or of their size.
+    fn traffic(
+        &mut self,
+        series: &[Series],
+        name: &str,
+        help: &str,
+        traffic: TrafficByType,
+        value: fn(&Traffic) -> u64,
+        filter: fn(&str) -> bool,
+    ) {
+        self.family(name, "counter", help);
+        for s in series {
+            for (kind, t) in traffic(&s.stats) {
+                if filter(kind) {
+                    self.sample(name, &s.labels_with("type", kind), value(t));
+                }
+            }
+        }
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::ServerMetrics;
+    use crate::{
+        commands::command_channel, interface::InRemoteMessageId, AdminSpaceClient, StateMap,
+    };
+
+    fn client(id: &str, listener: &str) -> AdminSpaceClient {
+        AdminSpaceClient::new(
+            id.to_string(),
+            "127.0.0.1:50000".to_string(),
+            listener.to_string(),
+            None,
+            None,
+            command_channel().0,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_export() {
+        let state_map = StateMap::default();
+        let metrics = ServerMetrics::new(state_map.clone());
+
+        let mut a = client("a", "[::]:10000");
+        a.register_received(InRemoteMessageId::DeclareSubscriber, 10);
+        a.register_received(InRemoteMessageId::UndeclareSubscriber, 5);
+        let mut b = client("b", "[::]:10000");
+        b.register_received(InRemoteMessageId::DeclareSubscriber, 20);
+        b.register_error();
+        state_map
+            .write()
+            .await
+            .insert("a".to_string(), Arc::new(Mutex::new(a.clone())));
+        state_map
+            .write()
+            .await
+            .insert("b".to_string(), Arc::new(Mutex::new(b)));
+        metrics.register_handshake_failure("[::]:10000");
+
+        let text = metrics.export(false).await;
+        assert!(text.contains("# TYPE zenoh_remote_api_clients gauge\n"));
+        assert!(text.contains("zenoh_remote_api_clients{listener=\"[::]:10000\"} 2\n"));
+        assert!(text.contains(
+            "zenoh_remote_api_received_bytes_total{listener=\"[::]:10000\",type=\"DeclareSubscriber\"} 30\n"
+        ));
+        assert!(text.contains(
+            "zenoh_remote_api_declarations_total{listener=\"[::]:10000\",type=\"DeclareSubscriber\"} 2\n"
+        ));
+        assert!(text.contains(
+            "zenoh_remote_api_undeclarations_total{listener=\"[::]:10000\",type=\"UndeclareSubscriber\"} 1\n"
+        ));
+        assert!(text.contains("zenoh_remote_api_errors_total{listener=\"[::]:10000\"} 1\n"));
+        assert!(
+            text.contains("zenoh_remote_api_handshake_failures_total{listener=\"[::]:10000\"} 1\n")
+        );
+
+        // the counters of a listener don't decrease when a session ends
+        state_map.write().await.remove("a");
+        metrics.register_session_end(&a);
+        let text = metrics.export(false).await;
+        assert!(text.contains("zenoh_remote_api_clients{listener=\"[::]:10000\"} 1\n"));
+        assert!(text.contains(
+            "zenoh_remote_api_received_messages_total{listener=\"[::]:10000\",type=\"DeclareSubscriber\"} 2\n"
+        ));
+
+        let text = metrics.export(true).await;
+        assert!(text.contains(
+            "zenoh_remote_api_received_messages_total{listener=\"[::]:10000\",client=\"b\",type=\"DeclareSubscriber\"} 1\n"
+        ));
+        assert!(!text.contains("client=\"a\""));
+    }
+}
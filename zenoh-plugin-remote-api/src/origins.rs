@@ -0,0 +1,131 @@
+//
+// Copyright (c) 2026 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use tokio_tungstenite::tungstenite::{
+    handshake::server::{ErrorResponse, Request},
+    http::{header, StatusCode},
+};
+
+use crate::config::CrossOrigin;
+
+const ANY: &str = "*";
+
+/// Strips the port of a `Host` header, keeping the brackets of IPv6 addresses.
+fn host_name(host: &str) -> &str {
+    match host.rsplit_once(':') {
+        Some((name, port))
+            if !port.is_empty()
+                && port.bytes().all(|b| b.is_ascii_digit())
+                && (!name.contains(':') || name.ends_with(']')) =>
+        {
+            name
+        }
+        _ => host,
+    }
+}
+
+fn origin_allowed(allowed: &[String], origin: &str) -> bool {
+    let origin = origin.trim_end_matches('/');
+    allowed
+        .iter()
+        .any(|a| a == ANY || a.trim_end_matches('/').eq_ignore_ascii_case(origin))
+}
+
+/// Allowed hosts without a port match the host on any port.
+fn host_allowed(allowed: &[String], host: &str) -> bool {
+    allowed.iter().any(|a| {
+        a == ANY || a.eq_ignore_ascii_case(host) || a.eq_ignore_ascii_case(host_name(host))
+    })
+}
+
+/// Checks the `Origin` and `Host` headers of a WebSocket upgrade request against the
+/// allow-lists, returns the reason of the rejection.
+pub(crate) fn check_request(config: &CrossOrigin, request: &Request) -> Result<(), String> {
+    let headers = request.headers();
+    if let (Some(allowed), Some(origin)) = (&config.allowed_origins, headers.get(header::ORIGIN)) {
+        let origin = origin.to_str().unwrap_or_default();
+        if !origin_allowed(allowed, origin) {
+            return Err(format!("Origin '{origin}' is not allowed"));
+        }
+    }
+    if let Some(allowed) = &config.allowed_hosts {
+        let Some(host) = headers.get(header::HOST) else {
+            return Err("Missing Host header".to_string());
+        };
+        let host = host.to_str().unwrap_or_default();
+        if !host_allowed(allowed, host) {
+            return Err(format!("Host '{host}' is not allowed"));
+        }
+    }
+    Ok(())
+}
+
+/// The response sent to clients whose origin or host is not allowed.
+pub(crate) fn forbidden(reason: &str) -> ErrorResponse {
+    let mut response = ErrorResponse::new(Some(reason.to_string()));
+    *response.status_mut() = StatusCode::FORBIDDEN;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_tungstenite::tungstenite::handshake::server::Request;
+
+    use super::{check_request, host_name};
+    use crate::config::CrossOrigin;
+
+    fn request(host: &str, origin: Option<&str>) -> Request {
+        let mut builder = Request::builder().uri("/").header("Host", host);
+        if let Some(origin) = origin {
+            builder = builder.header("Origin", origin);
+        }
+        builder.body(()).unwrap()
+    }
+
+    fn list(items: &[&str]) -> Option<Vec<String>> {
+        Some(items.iter().map(|i| i.to_string()).collect())
+    }
+
+    #[test]
+    fn test_host_name() {
+        assert_eq!(host_name("localhost:10000"), "localhost");
+        assert_eq!(host_name("localhost"), "localhost");
+        assert_eq!(host_name("[::1]:10000"), "[::1]");
+        assert_eq!(host_name("[::1]"), "[::1]");
+    }
+
+    #[test]
+    fn test_check_request() {
+        let config = CrossOrigin {
+            allowed_origins: list(&["https://app.example.com/"]),
+            allowed_hosts: list(&["localhost", "127.0.0.1:10000"]),
+        };
+        let origin = Some("https://APP.example.com");
+        assert!(check_request(&config, &request("localhost:10000", origin)).is_ok());
+        assert!(check_request(&config, &request("127.0.0.1:10000", origin)).is_ok());
+        // clients other than browsers don't send an origin
+        assert!(check_request(&config, &request("localhost:10000", None)).is_ok());
+
+        let evil = Some("https://evil.example.com");
+        assert!(check_request(&config, &request("localhost:10000", evil)).is_err());
+        assert!(check_request(&config, &request("127.0.0.1:8000", origin)).is_err());
+        assert!(check_request(&config, &request("attacker.example.com", origin)).is_err());
+
+        let config = CrossOrigin {
+            allowed_origins: list(&["*"]),
+            allowed_hosts: None,
+        };
+        assert!(check_request(&config, &request("anything", evil)).is_ok());
+    }
+}
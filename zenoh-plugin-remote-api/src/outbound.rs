@@ -0,0 +1,493 @@
+//
+// Copyright (c) 2026 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex, MutexGuard, Weak,
+    },
+};
+
+use tokio::{runtime::RuntimeFlavor, sync::Notify};
+use zenoh::qos::CongestionControl;
+
+use crate::{
+    config::{OutboundQueue, OverflowPolicy},
+    interface::{OutRemoteMessage, SequenceId},
+    AdminSpaceClient,
+};
+
+pub(crate) type OutboundMessage = (OutRemoteMessage, Option<SequenceId>);
+
+/// The outbound queue was closed, either because the client disconnected
+/// or because it overflowed with the `disconnect` policy.
+#[derive(Debug)]
+pub(crate) struct Closed;
+
+struct Shared {
+    queue: Mutex<VecDeque<OutboundMessage>>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    closed: AtomicBool,
+    /// Set while nothing drains the queue, between the connections of a resumable client.
+    suspended: AtomicBool,
+    not_full: Condvar,
+    not_empty: Notify,
+    overflow_disconnect: Notify,
+    admin_client: Arc<Mutex<AdminSpaceClient>>,
+}
+
+impl Shared {
+    fn close(&self) {
+        let queue = self.queue.lock().unwrap();
+        self.close_locked(&queue);
+    }
+
+    /// Closes the queue while it is locked, so that no sender misses the notification
+    /// between checking whether the queue is closed and waiting for room in it.
+    fn close_locked(&self, _queue: &VecDeque<OutboundMessage>) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.not_full.notify_all();
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    fn disconnect_on_overflow(&self, queue: &VecDeque<OutboundMessage>) {
+        self.close_locked(queue);
+        self.overflow_disconnect.notify_waiters();
+    }
+
+    /// Waits for room in the queue. A worker of a multi-threaded runtime hands its other tasks
+    /// over to another thread while it waits.
+    fn wait_not_full<'a>(
+        &self,
+        queue: MutexGuard<'a, VecDeque<OutboundMessage>>,
+    ) -> MutexGuard<'a, VecDeque<OutboundMessage>> {
+        let wait = || self.not_full.wait(queue).unwrap();
+        match tokio::runtime::Handle::try_current() {
+            Ok(_) => tokio::task::block_in_place(wait),
+            Err(_) => wait(),
+        }
+    }
+}
+
+/// A current-thread runtime would stall while waiting for room in the queue.
+fn can_wait() -> bool {
+    tokio::runtime::Handle::try_current().map_or(true, |rt| {
+        rt.runtime_flavor() != RuntimeFlavor::CurrentThread
+    })
+}
+
+/// Only samples published with `CongestionControl::Drop` may be dropped on overflow.
+fn is_droppable(msg: &OutRemoteMessage) -> bool {
+    match msg {
+        OutRemoteMessage::Sample(s) => s.sample.congestion_control() == CongestionControl::Drop,
+        _ => false,
+    }
+}
+
+/// Creates the queue of messages sent to a WebSocket client.
+/// Without configuration the queue is unbounded.
+pub(crate) fn outbound_channel(
+    config: Option<&OutboundQueue>,
+    admin_client: Arc<Mutex<AdminSpaceClient>>,
+) -> (OutboundSender, OutboundReceiver) {
+    let (capacity, policy) = match config {
+        Some(c) => (c.capacity.max(1), c.overflow_policy),
+        None => (usize::MAX, OverflowPolicy::default()),
+    };
+    let shared = Arc::new(Shared {
+        queue: Mutex::new(VecDeque::new()),
+        capacity,
+        policy,
+        closed: AtomicBool::new(false),
+        suspended: AtomicBool::new(false),
+        not_full: Condvar::new(),
+        not_empty: Notify::new(),
+        overflow_disconnect: Notify::new(),
+        admin_client,
+    });
+    shared
+        .admin_client
+        .lock()
+        .unwrap()
+        .set_outbound_queue(OutboundQueueDepth(Arc::downgrade(&shared)));
+    (
+        OutboundSender {
+            shared: shared.clone(),
+        },
+        OutboundReceiver { shared },
+    )
+}
+
+#[derive(Clone)]
+pub(crate) struct OutboundSender {
+    shared: Arc<Shared>,
+}
+
+impl OutboundSender {
+    /// Enqueues a message produced by a zenoh callback, applying the overflow policy.
+    /// May block the calling thread if the queue is full, without stalling the tasks
+    /// of the runtime. On a current-thread runtime the message is enqueued over capacity.
+    /// While the queue is suspended, a message which would wait overflows it as with
+    /// the `disconnect` policy.
+    pub(crate) fn send(&self, msg: OutboundMessage) -> Result<(), Closed> {
+        let shared = &self.shared;
+        let mut overflowed = false;
+        let mut queue = shared.queue.lock().unwrap();
+        let res = loop {
+            if shared.is_closed() {
+                break Err(Closed);
+            }
+            if queue.len() < shared.capacity {
+                queue.push_back(msg);
+                break Ok(());
+            }
+            overflowed = true;
+            let droppable = is_droppable(&msg.0);
+            match shared.policy {
+                OverflowPolicy::DropOldest => {
+                    if let Some(pos) = queue.iter().position(|(m, _)| is_droppable(m)) {
+                        queue.remove(pos);
+                        queue.push_back(msg);
+                        break Ok(());
+                    } else if droppable {
+                        break Ok(());
+                    }
+                }
+                OverflowPolicy::DropNewest if droppable => break Ok(()),
+                OverflowPolicy::Disconnect => {
+                    shared.disconnect_on_overflow(&queue);
+                    break Err(Closed);
+                }
+                _ => {}
+            }
+            // there would be no end to the wait, the client will have missed messages
+            if shared.suspended.load(Ordering::SeqCst) {
+                shared.disconnect_on_overflow(&queue);
+                break Err(Closed);
+            }
+            if !can_wait() {
+                queue.push_back(msg);
+                break Ok(());
+            }
+            queue = shared.wait_not_full(queue);
+        };
+        drop(queue);
+        if overflowed {
+            shared
+                .admin_client
+                .lock()
+                .unwrap()
+                .register_outbound_queue_overflow();
+        }
+        if res.is_ok() {
+            shared.not_empty.notify_one();
+        }
+        res
+    }
+
+    /// Enqueues a response to a request of the client.
+    /// Responses are never dropped and don't wait for room in the queue.
+    pub(crate) fn send_response(&self, msg: OutboundMessage) -> Result<(), Closed> {
+        if self.shared.is_closed() {
+            return Err(Closed);
+        }
+        self.shared.queue.lock().unwrap().push_back(msg);
+        self.shared.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Returns `true` once the client disconnected or overflowed with the `disconnect` policy.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.shared.is_closed()
+    }
+
+    /// Resolves when the client must be disconnected because its queue overflowed.
+    /// All the pending calls resolve, e.g. both the connection of a client and the
+    /// grace period of its session if it reconnected.
+    pub(crate) async fn overflow_disconnect(&self) {
+        loop {
+            let notified = self.shared.overflow_disconnect.notified();
+            if self.shared.is_closed() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// The number of messages in the queue of a client, which doesn't keep the queue alive.
+#[derive(Clone, Debug)]
+pub(crate) struct OutboundQueueDepth(Weak<Shared>);
+
+impl OutboundQueueDepth {
+    pub(crate) fn get(&self) -> usize {
+        self.0
+            .upgrade()
+            .map_or(0, |shared| shared.queue.lock().unwrap().len())
+    }
+}
+
+pub(crate) struct OutboundReceiver {
+    shared: Arc<Shared>,
+}
+
+impl OutboundReceiver {
+    pub(crate) fn try_recv(&self) -> Option<OutboundMessage> {
+        let msg = self.shared.queue.lock().unwrap().pop_front()?;
+        self.shared.not_full.notify_one();
+        Some(msg)
+    }
+
+    pub(crate) async fn recv(&self) -> OutboundMessage {
+        loop {
+            if let Some(msg) = self.try_recv() {
+                return msg;
+            }
+            self.shared.not_empty.notified().await;
+        }
+    }
+
+    /// Puts back at the front of the queue the messages received but not sent, in order.
+    pub(crate) fn requeue(&self, messages: Vec<OutboundMessage>) {
+        if messages.is_empty() {
+            return;
+        }
+        let mut queue = self.shared.queue.lock().unwrap();
+        for msg in messages.into_iter().rev() {
+            queue.push_front(msg);
+        }
+        drop(queue);
+        self.shared.not_empty.notify_one();
+    }
+
+    /// Stops waiting for room in the queue while nothing drains it,
+    /// releasing the callbacks already waiting.
+    pub(crate) fn suspend(&self) {
+        let _queue = self.shared.queue.lock().unwrap();
+        self.shared.suspended.store(true, Ordering::SeqCst);
+        self.shared.not_full.notify_all();
+    }
+
+    pub(crate) fn resume(&self) {
+        self.shared.suspended.store(false, Ordering::SeqCst);
+    }
+}
+
+impl Drop for OutboundReceiver {
+    fn drop(&mut self) {
+        // unblock the callbacks waiting for room in the queue
+        self.shared.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        thread,
+        time::Duration,
+    };
+
+    use zenoh::{key_expr::KeyExpr, qos::CongestionControl, sample::SampleBuilder};
+
+    use super::{outbound_channel, OutboundMessage, OutboundReceiver, OutboundSender};
+    use crate::{
+        commands::command_channel,
+        config::{OutboundQueue, OverflowPolicy},
+        interface::{self, InRemoteMessageId, OutRemoteMessage, SubscriberId},
+        AdminSpaceClient,
+    };
+
+    fn channel(
+        capacity: usize,
+        overflow_policy: OverflowPolicy,
+    ) -> (OutboundSender, OutboundReceiver) {
+        let admin_client = AdminSpaceClient::new(
+            "id".to_string(),
+            "127.0.0.1:10000".to_string(),
+            "10000".to_string(),
+            None,
+            None,
+            command_channel().0,
+        );
+        outbound_channel(
+            Some(&OutboundQueue {
+                capacity,
+                overflow_policy,
+            }),
+            Arc::new(Mutex::new(admin_client)),
+        )
+    }
+
+    fn sample(congestion_control: CongestionControl, seq: u32) -> OutboundMessage {
+        let sample = SampleBuilder::put(KeyExpr::new("demo/outbound").unwrap(), "payload")
+            .congestion_control(congestion_control)
+            .into();
+        let msg = OutRemoteMessage::Sample(interface::Sample {
+            subscriber_id: SubscriberId(1),
+            sample,
+        });
+        (msg, Some(seq))
+    }
+
+    fn ok(seq: u32) -> OutboundMessage {
+        let msg = OutRemoteMessage::Ok(interface::Ok {
+            content_id: InRemoteMessageId::Ping,
+        });
+        (msg, Some(seq))
+    }
+
+    fn drain(rx: &OutboundReceiver) -> Vec<u32> {
+        std::iter::from_fn(|| rx.try_recv())
+            .map(|(_, seq)| seq.unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_drop_newest() {
+        let (tx, rx) = channel(2, OverflowPolicy::DropNewest);
+        tx.send(sample(CongestionControl::Drop, 1)).unwrap();
+        tx.send(sample(CongestionControl::Drop, 2)).unwrap();
+        tx.send(sample(CongestionControl::Drop, 3)).unwrap();
+        assert_eq!(drain(&rx), [1, 2]);
+
+        // a message which may not be dropped can't wait on a current-thread runtime
+        tx.send(sample(CongestionControl::Drop, 4)).unwrap();
+        tx.send(sample(CongestionControl::Drop, 5)).unwrap();
+        tx.send(sample(CongestionControl::Block, 6)).unwrap();
+        assert_eq!(drain(&rx), [4, 5, 6]);
+        assert_eq!(
+            rx.shared
+                .admin_client
+                .lock()
+                .unwrap()
+                .outbound_queue_overflows,
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest() {
+        let (tx, rx) = channel(2, OverflowPolicy::DropOldest);
+        tx.send(sample(CongestionControl::Drop, 1)).unwrap();
+        tx.send(ok(2)).unwrap();
+        tx.send(sample(CongestionControl::Drop, 3)).unwrap();
+        assert_eq!(drain(&rx), [2, 3]);
+
+        // without a droppable message in the queue, the droppable message being sent is dropped
+        tx.send(ok(4)).unwrap();
+        tx.send(ok(5)).unwrap();
+        tx.send(sample(CongestionControl::Drop, 6)).unwrap();
+        assert_eq!(drain(&rx), [4, 5]);
+    }
+
+    #[test]
+    fn test_block() {
+        let (tx, rx) = channel(1, OverflowPolicy::Block);
+        tx.send(sample(CongestionControl::Drop, 1)).unwrap();
+        let sender = {
+            let tx = tx.clone();
+            thread::spawn(move || tx.send(sample(CongestionControl::Drop, 2)))
+        };
+        thread::sleep(Duration::from_millis(50));
+        assert!(!sender.is_finished());
+        assert_eq!(drain(&rx), [1]);
+        sender.join().unwrap().unwrap();
+        assert_eq!(drain(&rx), [2]);
+
+        // the senders waiting for room are released when the client is gone
+        tx.send(ok(3)).unwrap();
+        let sender = {
+            let tx = tx.clone();
+            thread::spawn(move || tx.send(ok(4)))
+        };
+        thread::sleep(Duration::from_millis(50));
+        drop(rx);
+        assert!(sender.join().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_close_while_blocked() {
+        for _ in 0..100 {
+            let (tx, rx) = channel(1, OverflowPolicy::Block);
+            tx.send(ok(1)).unwrap();
+            let (done_tx, done_rx) = std::sync::mpsc::channel();
+            thread::spawn(move || done_tx.send(tx.send(ok(2))));
+            // closes the queue while the sender checks it or waits for room
+            drop(rx);
+            let res = done_rx
+                .recv_timeout(Duration::from_secs(5))
+                .expect("the sender is released when the queue is closed");
+            assert!(res.is_err());
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_block_on_runtime() {
+        let (tx, rx) = channel(1, OverflowPolicy::Block);
+        tx.send(ok(1)).unwrap();
+        // the waiting sender doesn't keep the receiver from running
+        let sender = tokio::spawn(async move { tx.send(ok(2)) });
+        assert_eq!(rx.recv().await.1, Some(1));
+        sender.await.unwrap().unwrap();
+        assert_eq!(rx.recv().await.1, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_disconnect() {
+        let (tx, rx) = channel(1, OverflowPolicy::Disconnect);
+        tx.send(sample(CongestionControl::Drop, 1)).unwrap();
+        assert!(!tx.is_closed());
+        assert!(tx.send(sample(CongestionControl::Drop, 2)).is_err());
+        assert!(tx.is_closed());
+        tokio::time::timeout(Duration::from_secs(1), tx.overflow_disconnect())
+            .await
+            .unwrap();
+        assert!(tx.send_response(ok(3)).is_err());
+        assert_eq!(drain(&rx), [1]);
+    }
+
+    #[tokio::test]
+    async fn test_requeue() {
+        let (tx, rx) = channel(4, OverflowPolicy::Block);
+        for seq in 1..5 {
+            tx.send(ok(seq)).unwrap();
+        }
+        let in_flight = vec![rx.try_recv().unwrap(), rx.try_recv().unwrap()];
+        rx.requeue(in_flight);
+        assert_eq!(drain(&rx), [1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_send_response() {
+        let (tx, rx) = channel(1, OverflowPolicy::Block);
+        tx.send(ok(1)).unwrap();
+        tx.send_response(ok(2)).unwrap();
+        tx.send_response(ok(3)).unwrap();
+        assert_eq!(drain(&rx), [1, 2, 3]);
+        assert_eq!(
+            rx.shared
+                .admin_client
+                .lock()
+                .unwrap()
+                .outbound_queue_overflows,
+            0
+        );
+    }
+}
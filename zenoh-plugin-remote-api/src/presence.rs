@@ -0,0 +1,150 @@
+//
+// Copyright (c) 2026 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use tokio_tungstenite::tungstenite::handshake::server::Request;
+use uuid::Uuid;
+use zenoh::key_expr::{keyexpr, OwnedKeyExpr};
+use zenoh_result::{bail, zerror, ZResult};
+
+use crate::config::ClientLiveliness;
+
+/// The query parameter of the upgrade request by which a client names its application.
+const APP_QUERY_PARAMETER: &str = "app";
+
+/// Returns the application name of the upgrade request, if it is a valid key expression chunk.
+pub(crate) fn requested_app_name(request: &Request) -> Option<String> {
+    let query = request.uri().query()?;
+    let (_, app) =
+        form_urlencoded::parse(query.as_bytes()).find(|(name, _)| name == APP_QUERY_PARAMETER)?;
+    let valid = !app.contains('/')
+        && !app.starts_with('@')
+        && keyexpr::new(app.as_ref()).is_ok_and(|ke| !ke.is_wild());
+    if !valid {
+        tracing::warn!("Ignored the invalid application name '{app}'");
+        return None;
+    }
+    Some(app.into_owned())
+}
+
+/// The liveliness tokens announcing the clients, with a validated prefix.
+pub struct ClientPresence {
+    prefix: Option<OwnedKeyExpr>,
+    app_name: bool,
+}
+
+impl ClientPresence {
+    pub fn new(config: &ClientLiveliness) -> ZResult<Self> {
+        let prefix = match &config.prefix {
+            Some(prefix) => {
+                let prefix = OwnedKeyExpr::autocanonize(prefix.clone())
+                    .map_err(|e| zerror!("Invalid key expression '{}': {}", prefix, e))?;
+                if prefix.is_wild() {
+                    bail!("The prefix '{prefix}' must not contain wildcards");
+                }
+                Some(prefix)
+            }
+            None => None,
+        };
+        Ok(ClientPresence {
+            prefix,
+            app_name: config.app_name,
+        })
+    }
+
+    /// The key expression of the token of a client of the zenoh node `zid`.
+    pub(crate) fn key_expr(&self, zid: &keyexpr, id: &Uuid, app: Option<&str>) -> OwnedKeyExpr {
+        let prefix = match &self.prefix {
+            Some(prefix) => prefix.to_string(),
+            None => format!("@/{zid}/remote-plugin/clients"),
+        };
+        let key_expr = match app.filter(|_| self.app_name) {
+            Some(app) => format!("{prefix}/{id}/{app}"),
+            None => format!("{prefix}/{id}"),
+        };
+        // the prefix and the application name are validated beforehand
+        OwnedKeyExpr::autocanonize(key_expr).expect("valid client liveliness key expression")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_tungstenite::tungstenite::handshake::server::Request;
+    use uuid::Uuid;
+    use zenoh::key_expr::keyexpr;
+
+    use super::{requested_app_name, ClientPresence};
+    use crate::config::ClientLiveliness;
+
+    fn request(uri: &str) -> Request {
+        Request::builder().uri(uri).body(()).unwrap()
+    }
+
+    #[test]
+    fn test_requested_app_name() {
+        assert_eq!(requested_app_name(&request("ws://localhost:10000")), None);
+        assert_eq!(
+            requested_app_name(&request("ws://localhost:10000/?token=abc&app=dashboard"))
+                .as_deref(),
+            Some("dashboard")
+        );
+        assert_eq!(
+            requested_app_name(&request("ws://localhost:10000/?app=a%2Fb")),
+            None
+        );
+        assert_eq!(
+            requested_app_name(&request("ws://localhost:10000/?app=*")),
+            None
+        );
+        assert_eq!(
+            requested_app_name(&request("ws://localhost:10000/?app=")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_key_expr() {
+        let zid = keyexpr::new("a1b2").unwrap();
+        let id = Uuid::nil();
+
+        let presence = ClientPresence::new(&ClientLiveliness {
+            prefix: None,
+            app_name: false,
+        })
+        .unwrap();
+        assert_eq!(
+            presence.key_expr(zid, &id, Some("dashboard")).as_str(),
+            format!("@/a1b2/remote-plugin/clients/{id}")
+        );
+
+        let presence = ClientPresence::new(&ClientLiveliness {
+            prefix: Some("remote-api/clients".to_string()),
+            app_name: true,
+        })
+        .unwrap();
+        assert_eq!(
+            presence.key_expr(zid, &id, Some("dashboard")).as_str(),
+            format!("remote-api/clients/{id}/dashboard")
+        );
+        assert_eq!(
+            presence.key_expr(zid, &id, None).as_str(),
+            format!("remote-api/clients/{id}")
+        );
+
+        assert!(ClientPresence::new(&ClientLiveliness {
+            prefix: Some("remote-api/*".to_string()),
+            app_name: false,
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,154 @@
+//
+// Copyright (c) 2026 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use zenoh_result::{bail, ZResult};
+
+use crate::interface::{Hello, HelloAck};
+
+/// The latest version of the protocol spoken by the plugin.
+pub(crate) const PROTOCOL_VERSION: u32 = 3;
+/// The version of the protocol spoken by the clients which don't send a `Hello` message.
+pub(crate) const LEGACY_PROTOCOL_VERSION: u32 = 1;
+
+/// The optional features of the protocol, as bits of the `Hello` and `HelloAck` messages.
+pub(crate) const FEATURE_RESUMPTION: u32 = 1 << 0;
+pub(crate) const FEATURE_COMPRESSION: u32 = 1 << 1;
+pub(crate) const FEATURE_BATCHING: u32 = 1 << 2;
+
+/// Checks the oldest version of the protocol accepted from the clients, which can't be
+/// newer than the latest version spoken by the plugin.
+pub(crate) fn check_min_version(min_version: Option<u32>) -> ZResult<()> {
+    match min_version {
+        Some(min_version) if min_version > PROTOCOL_VERSION => bail!(
+            "`min_protocol_version` {min_version} is newer than the latest supported version {}",
+            PROTOCOL_VERSION
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// The negotiation of the version of the protocol spoken with a client.
+pub(crate) struct Negotiation {
+    min_version: u32,
+    /// The features enabled on the connection.
+    features: u32,
+    negotiated: bool,
+    rejection: Option<String>,
+}
+
+impl Negotiation {
+    pub(crate) fn new(min_version: u32, features: u32) -> Self {
+        Negotiation {
+            min_version,
+            features,
+            negotiated: false,
+            rejection: None,
+        }
+    }
+
+    /// Answers the `Hello` message of a client with the latest version supported by both,
+    /// and the features both support. `None` if there is no such version.
+    pub(crate) fn hello(&mut self, hello: &Hello) -> Option<HelloAck> {
+        let version = hello.version.min(PROTOCOL_VERSION);
+        if version < hello.min_version.max(self.min_version) {
+            self.rejection = Some(format!(
+                "Incompatible protocol versions: the client supports {}..={}, the server {}..={}",
+                hello.min_version, hello.version, self.min_version, PROTOCOL_VERSION
+            ));
+            return None;
+        }
+        self.negotiated = true;
+        Some(HelloAck {
+            version,
+            features: hello.features & self.features,
+        })
+    }
+
+    /// Checks the version of a client sending a message other than `Hello`,
+    /// which is the legacy version if it didn't send one before.
+    pub(crate) fn check(&mut self) -> Option<()> {
+        if !self.negotiated && LEGACY_PROTOCOL_VERSION < self.min_version {
+            self.rejection = Some(format!(
+                "Protocol version {LEGACY_PROTOCOL_VERSION} is not supported, \
+                 a Hello message with version {} or later is required",
+                self.min_version
+            ));
+        }
+        self.rejection.is_none().then_some(())
+    }
+
+    /// Why the client was rejected, if it was.
+    pub(crate) fn rejection(&self) -> Option<&str> {
+        self.rejection.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_min_version, Negotiation, FEATURE_BATCHING, FEATURE_COMPRESSION, FEATURE_RESUMPTION,
+        LEGACY_PROTOCOL_VERSION, PROTOCOL_VERSION,
+    };
+    use crate::interface::Hello;
+
+    #[test]
+    fn test_check_min_version() {
+        assert!(check_min_version(None).is_ok());
+        assert!(check_min_version(Some(LEGACY_PROTOCOL_VERSION)).is_ok());
+        assert!(check_min_version(Some(PROTOCOL_VERSION)).is_ok());
+        assert!(check_min_version(Some(PROTOCOL_VERSION + 1)).is_err());
+    }
+
+    #[test]
+    fn test_negotiation() {
+        let features = FEATURE_RESUMPTION | FEATURE_BATCHING;
+
+        let mut negotiation = Negotiation::new(LEGACY_PROTOCOL_VERSION, features);
+        assert!(negotiation.check().is_some());
+        let ack = negotiation
+            .hello(&Hello {
+                version: PROTOCOL_VERSION + 1,
+                min_version: LEGACY_PROTOCOL_VERSION,
+                features: FEATURE_COMPRESSION | FEATURE_BATCHING,
+            })
+            .unwrap();
+        assert_eq!(ack.version, PROTOCOL_VERSION);
+        assert_eq!(ack.features, FEATURE_BATCHING);
+
+        // a client requiring a newer version
+        let mut negotiation = Negotiation::new(LEGACY_PROTOCOL_VERSION, features);
+        assert!(negotiation
+            .hello(&Hello {
+                version: PROTOCOL_VERSION + 2,
+                min_version: PROTOCOL_VERSION + 1,
+                features: 0,
+            })
+            .is_none());
+        assert!(negotiation.rejection().is_some());
+        assert!(negotiation.check().is_none());
+
+        // legacy clients, when they are not supported
+        let mut negotiation = Negotiation::new(PROTOCOL_VERSION, features);
+        assert!(negotiation.check().is_none());
+        let mut negotiation = Negotiation::new(PROTOCOL_VERSION, features);
+        assert!(negotiation
+            .hello(&Hello {
+                version: PROTOCOL_VERSION,
+                min_version: PROTOCOL_VERSION,
+                features: 0,
+            })
+            .is_some());
+        assert!(negotiation.check().is_some());
+    }
+}
@@ -0,0 +1,304 @@
+//
+// Copyright (c) 2026 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+};
+
+use serde::Serialize;
+use zenoh_result::{bail, ZResult};
+
+use crate::{
+    config::{EntityLimits, Quotas},
+    interface::InRemoteMessage,
+};
+
+/// The types of entities a client can declare, each with its own quota.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum EntityKind {
+    Publisher,
+    Subscriber,
+    Queryable,
+    Querier,
+    LivelinessToken,
+    LivelinessSubscriber,
+    Listener,
+}
+
+const ENTITY_KINDS: [EntityKind; 7] = [
+    EntityKind::Publisher,
+    EntityKind::Subscriber,
+    EntityKind::Queryable,
+    EntityKind::Querier,
+    EntityKind::LivelinessToken,
+    EntityKind::LivelinessSubscriber,
+    EntityKind::Listener,
+];
+
+impl EntityKind {
+    fn name(self) -> &'static str {
+        match self {
+            EntityKind::Publisher => "publishers",
+            EntityKind::Subscriber => "subscribers",
+            EntityKind::Queryable => "queryables",
+            EntityKind::Querier => "queriers",
+            EntityKind::LivelinessToken => "liveliness_tokens",
+            EntityKind::LivelinessSubscriber => "liveliness_subscribers",
+            EntityKind::Listener => "listeners",
+        }
+    }
+
+    fn limit(self, limits: &EntityLimits) -> Option<usize> {
+        match self {
+            EntityKind::Publisher => limits.publishers,
+            EntityKind::Subscriber => limits.subscribers,
+            EntityKind::Queryable => limits.queryables,
+            EntityKind::Querier => limits.queriers,
+            EntityKind::LivelinessToken => limits.liveliness_tokens,
+            EntityKind::LivelinessSubscriber => limits.liveliness_subscribers,
+            EntityKind::Listener => limits.listeners,
+        }
+    }
+
+    /// Returns the kind of entity declared by a message, if any.
+    pub(crate) fn declared_by(msg: &InRemoteMessage) -> Option<Self> {
+        match msg {
+            InRemoteMessage::DeclarePublisher(_) | InRemoteMessage::DeclareAdvancedPublisher(_) => {
+                Some(EntityKind::Publisher)
+            }
+            InRemoteMessage::DeclareSubscriber(_)
+            | InRemoteMessage::DeclareAdvancedSubscriber(_) => Some(EntityKind::Subscriber),
+            InRemoteMessage::DeclareQueryable(_) => Some(EntityKind::Queryable),
+            InRemoteMessage::DeclareQuerier(_) => Some(EntityKind::Querier),
+            InRemoteMessage::DeclareLivelinessToken(_) => Some(EntityKind::LivelinessToken),
+            InRemoteMessage::DeclareLivelinessSubscriber(_) => {
+                Some(EntityKind::LivelinessSubscriber)
+            }
+            InRemoteMessage::PublisherDeclareMatchingListener(_)
+            | InRemoteMessage::QuerierDeclareMatchingListener(_)
+            | InRemoteMessage::DeclareTransportEventsListener(_)
+            | InRemoteMessage::DeclareLinkEventsListener(_) => Some(EntityKind::Listener),
+            _ => None,
+        }
+    }
+
+    /// Returns the kind of entity undeclared by a message, if any.
+    pub(crate) fn undeclared_by(msg: &InRemoteMessage) -> Option<Self> {
+        match msg {
+            InRemoteMessage::UndeclarePublisher(_) => Some(EntityKind::Publisher),
+            InRemoteMessage::UndeclareSubscriber(_) => Some(EntityKind::Subscriber),
+            InRemoteMessage::UndeclareQueryable(_) => Some(EntityKind::Queryable),
+            InRemoteMessage::UndeclareQuerier(_) => Some(EntityKind::Querier),
+            InRemoteMessage::UndeclareLivelinessToken(_) => Some(EntityKind::LivelinessToken),
+            InRemoteMessage::UndeclareLivelinessSubscriber(_) => {
+                Some(EntityKind::LivelinessSubscriber)
+            }
+            InRemoteMessage::UndeclareMatchingListener(_)
+            | InRemoteMessage::UndeclareTransportEventsListener(_)
+            | InRemoteMessage::UndeclareLinkEventsListener(_) => Some(EntityKind::Listener),
+            _ => None,
+        }
+    }
+}
+
+/// The number of entities declared of each kind.
+pub(crate) type EntityUsage = BTreeMap<&'static str, usize>;
+
+/// The quotas shared by all the clients, with the number of entities they declared.
+#[derive(Default)]
+pub struct GlobalQuotas {
+    config: RwLock<Quotas>,
+    usage: [AtomicUsize; ENTITY_KINDS.len()],
+}
+
+#[derive(Serialize)]
+struct QuotasStatus<'a> {
+    limits: &'a Quotas,
+    usage: EntityUsage,
+}
+
+impl GlobalQuotas {
+    pub(crate) fn new(config: Option<&Quotas>) -> Self {
+        GlobalQuotas {
+            config: RwLock::new(config.cloned().unwrap_or_default()),
+            ..Default::default()
+        }
+    }
+
+    /// Changes the limits of the following declarations of all the clients.
+    /// The entities already declared are kept, and still counted.
+    pub(crate) fn reconfigure(&self, config: Option<&Quotas>) {
+        *self.config.write().unwrap() = config.cloned().unwrap_or_default();
+    }
+
+    fn usage(&self, kind: EntityKind) -> &AtomicUsize {
+        &self.usage[kind as usize]
+    }
+
+    /// The limits and the current usage, as reported in the admin space.
+    pub(crate) fn status(&self) -> serde_json::Result<serde_json::Value> {
+        serde_json::to_value(QuotasStatus {
+            limits: &self.config.read().unwrap(),
+            usage: ENTITY_KINDS
+                .iter()
+                .map(|k| (k.name(), self.usage(*k).load(Ordering::Relaxed)))
+                .collect(),
+        })
+    }
+}
+
+/// The quotas of a client, counting the entities it declared.
+/// They are released from the global usage when the client is dropped.
+pub(crate) struct ClientQuotas {
+    global: Arc<GlobalQuotas>,
+    usage: [usize; ENTITY_KINDS.len()],
+}
+
+impl ClientQuotas {
+    pub(crate) fn new(global: Arc<GlobalQuotas>) -> Self {
+        ClientQuotas {
+            global,
+            usage: [0; ENTITY_KINDS.len()],
+        }
+    }
+
+    /// Counts an entity about to be declared, fails if it would exceed a quota.
+    pub(crate) fn acquire(&mut self, kind: EntityKind) -> ZResult<()> {
+        let usage = &mut self.usage[kind as usize];
+        let config = self.global.config.read().unwrap();
+        if let Some(limit) = kind.limit(&config.per_client) {
+            if *usage >= limit {
+                bail!(
+                    "Quota exceeded: a client can't declare more than {limit} {}",
+                    kind.name()
+                );
+            }
+        }
+        let limit = kind.limit(&config.global).unwrap_or(usize::MAX);
+        drop(config);
+        if self
+            .global
+            .usage(kind)
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < limit).then_some(n + 1)
+            })
+            .is_err()
+        {
+            bail!(
+                "Quota exceeded: all the clients can't declare more than {limit} {}",
+                kind.name()
+            );
+        }
+        *usage += 1;
+        Ok(())
+    }
+
+    /// Releases an entity which was undeclared, or which failed to be declared.
+    pub(crate) fn release(&mut self, kind: EntityKind) {
+        let usage = &mut self.usage[kind as usize];
+        if *usage > 0 {
+            *usage -= 1;
+            self.global.usage(kind).fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+
+    pub(crate) fn usage(&self) -> EntityUsage {
+        ENTITY_KINDS
+            .iter()
+            .map(|k| (k.name(), self.usage[*k as usize]))
+            .collect()
+    }
+}
+
+impl Drop for ClientQuotas {
+    fn drop(&mut self) {
+        for kind in ENTITY_KINDS {
+            self.global
+                .usage(kind)
+                .fetch_sub(self.usage[kind as usize], Ordering::AcqRel);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{ClientQuotas, EntityKind, GlobalQuotas};
+    use crate::config::{EntityLimits, Quotas};
+
+    #[test]
+    fn test_quotas() {
+        let global = Arc::new(GlobalQuotas::new(Some(&Quotas {
+            per_client: EntityLimits {
+                subscribers: Some(2),
+                ..Default::default()
+            },
+            global: EntityLimits {
+                subscribers: Some(3),
+                ..Default::default()
+            },
+        })));
+        let mut client1 = ClientQuotas::new(global.clone());
+        let mut client2 = ClientQuotas::new(global.clone());
+
+        assert!(client1.acquire(EntityKind::Subscriber).is_ok());
+        assert!(client1.acquire(EntityKind::Subscriber).is_ok());
+        assert!(client1.acquire(EntityKind::Subscriber).is_err());
+        assert!(client1.acquire(EntityKind::Publisher).is_ok());
+
+        assert!(client2.acquire(EntityKind::Subscriber).is_ok());
+        assert!(client2.acquire(EntityKind::Subscriber).is_err());
+        client1.release(EntityKind::Subscriber);
+        assert!(client2.acquire(EntityKind::Subscriber).is_ok());
+        assert_eq!(client2.usage()["subscribers"], 2);
+
+        drop(client2);
+        let status = global.status().unwrap();
+        assert_eq!(status["usage"]["subscribers"], 1);
+        assert_eq!(status["usage"]["publishers"], 1);
+        assert_eq!(status["limits"]["global"]["subscribers"], 3);
+    }
+
+    #[test]
+    fn test_reconfigure() {
+        let limit = |subscribers| Quotas {
+            per_client: EntityLimits::default(),
+            global: EntityLimits {
+                subscribers: Some(subscribers),
+                ..Default::default()
+            },
+        };
+        let global = Arc::new(GlobalQuotas::new(Some(&limit(3))));
+        let mut client = ClientQuotas::new(global.clone());
+        for _ in 0..3 {
+            client.acquire(EntityKind::Subscriber).unwrap();
+        }
+
+        // the entities already declared still count against the new limits
+        global.reconfigure(Some(&limit(4)));
+        assert!(client.acquire(EntityKind::Subscriber).is_ok());
+        assert!(client.acquire(EntityKind::Subscriber).is_err());
+        global.reconfigure(Some(&limit(2)));
+        client.release(EntityKind::Subscriber);
+        assert!(client.acquire(EntityKind::Subscriber).is_err());
+        global.reconfigure(None);
+        assert!(client.acquire(EntityKind::Subscriber).is_ok());
+        assert_eq!(global.status().unwrap()["usage"]["subscribers"], 4);
+    }
+}
@@ -0,0 +1,255 @@
+//
+// Copyright (c) 2026 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::time::{Duration, Instant};
+
+use zenoh::key_expr::{keyexpr, OwnedKeyExpr};
+use zenoh_result::{zerror, ZResult};
+
+use crate::config::{RateLimit, RateLimitAction};
+
+/// A bucket refilled with `rate` tokens per second, holding up to one second of tokens.
+struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u64, now: Instant) -> Self {
+        let rate = rate.max(1) as f64;
+        TokenBucket {
+            rate,
+            tokens: rate,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.rate);
+        self.last_refill = now;
+    }
+
+    /// Returns how long to wait before `amount` tokens can be taken.
+    /// An amount larger than the bucket only requires it to be full, and leaves it in debt.
+    fn wait_time(&self, amount: f64) -> Duration {
+        let missing = amount.min(self.rate) - self.tokens;
+        if missing > 0.0 {
+            Duration::from_secs_f64(missing / self.rate)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+struct Rule {
+    key_exprs: Vec<OwnedKeyExpr>,
+    action: RateLimitAction,
+    messages_per_second: Option<u64>,
+    bytes_per_second: Option<u64>,
+}
+
+/// The rate limits of the plugin, with validated key expressions.
+pub struct RateLimits {
+    rules: Vec<Rule>,
+}
+
+impl RateLimits {
+    pub(crate) fn new(config: &[RateLimit]) -> ZResult<Self> {
+        let rules = config
+            .iter()
+            .map(|limit| {
+                let key_exprs = limit
+                    .key_exprs
+                    .iter()
+                    .map(|ke| {
+                        OwnedKeyExpr::autocanonize(ke.clone()).map_err(|e| {
+                            zerror!("Invalid key expression '{}' in rate limit: {}", ke, e)
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Rule {
+                    key_exprs,
+                    action: limit.on_limit,
+                    messages_per_second: limit.messages_per_second,
+                    bytes_per_second: limit.bytes_per_second,
+                })
+            })
+            .collect::<ZResult<Vec<_>>>()?;
+        Ok(RateLimits { rules })
+    }
+
+    /// Returns the token buckets of a new client, full.
+    pub(crate) fn client_limiter(&self) -> ClientRateLimiter {
+        let now = Instant::now();
+        ClientRateLimiter {
+            limiters: self
+                .rules
+                .iter()
+                .map(|rule| Limiter {
+                    key_exprs: rule.key_exprs.clone(),
+                    action: rule.action,
+                    messages: rule.messages_per_second.map(|r| TokenBucket::new(r, now)),
+                    bytes: rule.bytes_per_second.map(|r| TokenBucket::new(r, now)),
+                })
+                .collect(),
+        }
+    }
+}
+
+struct Limiter {
+    key_exprs: Vec<OwnedKeyExpr>,
+    action: RateLimitAction,
+    messages: Option<TokenBucket>,
+    bytes: Option<TokenBucket>,
+}
+
+impl Limiter {
+    fn applies_to(&self, key_expr: &keyexpr) -> bool {
+        self.key_exprs.is_empty() || self.key_exprs.iter().any(|ke| ke.intersects(key_expr))
+    }
+
+    fn buckets(&mut self) -> impl Iterator<Item = (&mut TokenBucket, bool)> {
+        let messages = self.messages.as_mut().map(|b| (b, false));
+        let bytes = self.bytes.as_mut().map(|b| (b, true));
+        messages.into_iter().chain(bytes)
+    }
+}
+
+/// A message exceeding a rate limit of a client.
+#[derive(Debug, PartialEq)]
+pub(crate) enum RateLimited {
+    /// The message must be rejected.
+    Rejected,
+    /// The message can be processed after waiting for the buckets to refill.
+    Wait(Duration),
+}
+
+/// The token buckets of a client.
+pub(crate) struct ClientRateLimiter {
+    limiters: Vec<Limiter>,
+}
+
+impl ClientRateLimiter {
+    /// Takes a message of `bytes` payload bytes on a key expression from the buckets
+    /// of the limits applying to it, unless one of them is exceeded.
+    pub(crate) fn acquire(
+        &mut self,
+        key_expr: &keyexpr,
+        bytes: usize,
+        now: Instant,
+    ) -> Result<(), RateLimited> {
+        let mut wait = Duration::ZERO;
+        for limiter in self.limiters.iter_mut().filter(|l| l.applies_to(key_expr)) {
+            let action = limiter.action;
+            for (bucket, is_bytes) in limiter.buckets() {
+                bucket.refill(now);
+                let amount = if is_bytes { bytes as f64 } else { 1.0 };
+                let bucket_wait = bucket.wait_time(amount);
+                if !bucket_wait.is_zero() && action == RateLimitAction::Error {
+                    return Err(RateLimited::Rejected);
+                }
+                wait = wait.max(bucket_wait);
+            }
+        }
+        if !wait.is_zero() {
+            return Err(RateLimited::Wait(wait));
+        }
+        for limiter in self.limiters.iter_mut().filter(|l| l.applies_to(key_expr)) {
+            for (bucket, is_bytes) in limiter.buckets() {
+                bucket.tokens -= if is_bytes { bytes as f64 } else { 1.0 };
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use zenoh::key_expr::keyexpr;
+
+    use super::{RateLimited, RateLimits};
+    use crate::config::{RateLimit, RateLimitAction};
+
+    fn ke(s: &str) -> &keyexpr {
+        keyexpr::new(s).unwrap()
+    }
+
+    fn limit(
+        messages_per_second: Option<u64>,
+        bytes_per_second: Option<u64>,
+        key_exprs: &[&str],
+        on_limit: RateLimitAction,
+    ) -> RateLimit {
+        RateLimit {
+            messages_per_second,
+            bytes_per_second,
+            key_exprs: key_exprs.iter().map(|k| k.to_string()).collect(),
+            on_limit,
+        }
+    }
+
+    #[test]
+    fn test_messages_backpressure() {
+        let limits =
+            RateLimits::new(&[limit(Some(2), None, &[], RateLimitAction::Backpressure)]).unwrap();
+        let mut limiter = limits.client_limiter();
+        let now = Instant::now();
+        assert!(limiter.acquire(ke("demo/a"), 0, now).is_ok());
+        assert!(limiter.acquire(ke("demo/a"), 0, now).is_ok());
+        assert_eq!(
+            limiter.acquire(ke("demo/a"), 0, now),
+            Err(RateLimited::Wait(Duration::from_millis(500)))
+        );
+        let later = now + Duration::from_millis(500);
+        assert!(limiter.acquire(ke("demo/a"), 0, later).is_ok());
+    }
+
+    #[test]
+    fn test_bytes_error() {
+        let limits = RateLimits::new(&[limit(
+            None,
+            Some(100),
+            &["demo/limited/**"],
+            RateLimitAction::Error,
+        )])
+        .unwrap();
+        let mut limiter = limits.client_limiter();
+        let now = Instant::now();
+        assert!(limiter.acquire(ke("demo/other"), 1000, now).is_ok());
+        // a message larger than the bucket is accepted when it is full
+        assert!(limiter.acquire(ke("demo/limited/a"), 150, now).is_ok());
+        assert_eq!(
+            limiter.acquire(ke("demo/limited/a"), 10, now),
+            Err(RateLimited::Rejected)
+        );
+        assert_eq!(
+            limiter.acquire(ke("demo/**"), 10, now + Duration::from_millis(100)),
+            Err(RateLimited::Rejected)
+        );
+        assert!(limiter
+            .acquire(ke("demo/limited/a"), 10, now + Duration::from_millis(600))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_invalid_key_expr() {
+        assert!(
+            RateLimits::new(&[limit(Some(1), None, &["demo/?"], RateLimitAction::Error)]).is_err()
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//
+// Copyright (c) 2026 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::sync::{Arc, OnceLock};
+
+use jsonschema::JSONSchema;
+use schemars::schema_for;
+use serde_json::{Map, Value};
+use zenoh_result::{bail, zerror, ZResult};
+
+use crate::{
+    config::Config,
+    listeners::{prepare_listeners, PreparedListener},
+    protocol,
+    ratelimit::RateLimits,
+};
+
+/// The settings which can be changed while the plugin is running.
+/// Changes only apply to the clients connecting afterwards, except for the quotas
+/// which apply to the following declarations of all the clients.
+const RUNTIME_CONFIGURABLE: &[&str] = &[
+    "websocket_port",
+    "secure_websocket",
+    "listeners",
+    "http",
+    "cross_origin",
+    "outbound_queue",
+    "compression",
+    "batching",
+    "connection_limits",
+    "message_limits",
+    "session_resumption",
+    "min_protocol_version",
+    "quotas",
+    "rate_limits",
+];
+
+/// A configuration change accepted by the plugin, to be applied by the WebSocket server.
+pub struct ConfigUpdate {
+    pub(crate) config: Arc<Config>,
+    pub(crate) listeners: Vec<PreparedListener>,
+    pub(crate) rate_limits: RateLimits,
+}
+
+/// The schema of the configuration, compiled once.
+fn schema() -> ZResult<&'static JSONSchema> {
+    static SCHEMA: OnceLock<Result<JSONSchema, String>> = OnceLock::new();
+    let schema = SCHEMA.get_or_init(|| {
+        let schema = serde_json::to_value(schema_for!(Config))
+            .map_err(|e| format!("Unable to generate the configuration schema: {e}"))?;
+        JSONSchema::compile(&schema)
+            .map_err(|e| format!("Unable to compile the configuration schema: {e}"))
+    });
+    Ok(schema.as_ref().map_err(|e| zerror!("{e}"))?)
+}
+
+fn validate_schema(config: &Map<String, Value>) -> ZResult<()> {
+    let schema = schema()?;
+    // The `__<name>__` fields are set by zenohd, not by the user
+    let config = Value::Object(
+        config
+            .iter()
+            .filter(|(k, _)| !k.starts_with("__"))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect(),
+    );
+    if let Err(errors) = schema.validate(&config) {
+        let errors = errors.map(|e| e.to_string()).collect::<Vec<_>>().join(", ");
+        bail!("Invalid configuration: {errors}");
+    }
+    Ok(())
+}
+
+/// Checks a configuration change requested through the admin space, and loads the
+/// resources it requires. The addresses of new listeners are bound when it is applied.
+pub(crate) fn check_config_change(
+    current: &Map<String, Value>,
+    new: &Map<String, Value>,
+) -> ZResult<ConfigUpdate> {
+    validate_schema(new)?;
+    let current: Config = serde_json::from_value(Value::Object(current.clone()))
+        .map_err(|e| zerror!("Invalid current configuration: {e}"))?;
+    let new: Config = serde_json::from_value(Value::Object(new.clone()))
+        .map_err(|e| zerror!("Invalid configuration: {e}"))?;
+
+    // compare the deserialized configurations so that default values are taken into account
+    let (Value::Object(current_fields), Value::Object(new_fields)) =
+        (Value::from(&current), Value::from(&new))
+    else {
+        bail!("Configuration is not an object");
+    };
+    // the tokens are redacted when serialized
+    if current.authentication != new.authentication {
+        bail!("Runtime change of `authentication` is not supported");
+    }
+    for (field, value) in new_fields.iter() {
+        if !field.starts_with("__")
+            && current_fields.get(field) != Some(value)
+            && !RUNTIME_CONFIGURABLE.contains(&field.as_str())
+        {
+            bail!("Runtime change of `{field}` is not supported");
+        }
+    }
+
+    protocol::check_min_version(new.min_protocol_version)?;
+    let listeners = prepare_listeners(&new)?;
+    let rate_limits = RateLimits::new(&new.rate_limits)?;
+
+    Ok(ConfigUpdate {
+        config: Arc::new(new),
+        listeners,
+        rate_limits,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use serde_json::{json, Map, Value};
+    use zenoh::key_expr::keyexpr;
+
+    use super::check_config_change;
+
+    fn map(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_runtime_configurable_change() {
+        let current = map(json!({"websocket_port": "10000", "__required__": true}));
+        let new = map(json!({
+            "websocket_port": "10000",
+            "outbound_queue": {"capacity": 100},
+            "__required__": true
+        }));
+        let update = check_config_change(&current, &new).unwrap();
+        assert_eq!(update.listeners.len(), 1);
+        assert!(update.listeners[0].socket.is_none());
+        assert!(update.listeners[0].tls_config.is_none());
+        assert_eq!(update.config.outbound_queue.as_ref().unwrap().capacity, 100);
+    }
+
+    #[test]
+    fn test_new_listener_not_bound() {
+        // an address in use can't be bound, checking the change must not try to
+        let socket = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let bind = socket.local_addr().unwrap().to_string();
+        let current = map(json!({"websocket_port": "10000"}));
+        let new = map(json!({"listeners": [{"bind": "10000"}, {"bind": bind}]}));
+        let update = check_config_change(&current, &new).unwrap();
+        assert!(update.listeners.iter().all(|l| l.socket.is_none()));
+    }
+
+    #[test]
+    fn test_quotas_and_rate_limits_change() {
+        let current = map(json!({"websocket_port": "10000"}));
+        let new = map(json!({
+            "websocket_port": "10000",
+            "quotas": {"per_client": {"subscribers": 10}},
+            "rate_limits": [{"key_exprs": ["demo/**"], "messages_per_second": 1, "on_limit": "error"}]
+        }));
+        let update = check_config_change(&current, &new).unwrap();
+        assert_eq!(
+            update
+                .config
+                .quotas
+                .as_ref()
+                .unwrap()
+                .per_client
+                .subscribers,
+            Some(10)
+        );
+        let mut limiter = update.rate_limits.client_limiter();
+        let key_expr = keyexpr::new("demo/a").unwrap();
+        let now = Instant::now();
+        assert!(limiter.acquire(key_expr, 0, now).is_ok());
+        assert!(limiter.acquire(key_expr, 0, now).is_err());
+
+        let new = map(json!({
+            "websocket_port": "10000",
+            "rate_limits": [{"key_exprs": ["demo/**/**/"], "messages_per_second": 100}]
+        }));
+        assert!(check_config_change(&current, &new).is_err());
+    }
+
+    #[test]
+    fn test_unsupported_change() {
+        let current = map(json!({"websocket_port": "10000"}));
+        let new = map(json!({
+            "websocket_port": "10000",
+            "authentication": {"tokens": [{"user": "alice", "token": "secret"}]}
+        }));
+        assert!(check_config_change(&current, &new).is_err());
+    }
+
+    #[test]
+    fn test_token_change() {
+        let tokens = |token| json!({"tokens": [{"user": "alice", "token": token}]});
+        let current = map(json!({"websocket_port": "10000", "authentication": tokens("secret")}));
+        let new = map(json!({"websocket_port": "10000", "authentication": tokens("other")}));
+        assert!(check_config_change(&current, &new).is_err());
+    }
+
+    #[test]
+    fn test_schema_validation() {
+        let current = map(json!({"websocket_port": "10000"}));
+        let new = map(json!({
+            "websocket_port": "10000",
+            "outbound_queue": {"capacity": "unbounded"}
+        }));
+        assert!(check_config_change(&current, &new).is_err());
+    }
+
+    #[test]
+    fn test_min_protocol_version_change() {
+        let current = map(json!({"websocket_port": "10000"}));
+        let new = map(json!({"websocket_port": "10000", "min_protocol_version": 3}));
+        assert!(check_config_change(&current, &new).is_ok());
+        let new = map(json!({"websocket_port": "10000", "min_protocol_version": 4}));
+        assert!(check_config_change(&current, &new).is_err());
+    }
+}
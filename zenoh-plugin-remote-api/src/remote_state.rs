@@ -21,40 +21,52 @@
 use std::{
     collections::HashMap,
     sync::{atomic::AtomicU32, Arc, Mutex},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use flume::Sender;
 use lru::LruCache;
 use zenoh::{
     config::{Locator, WhatAmI},
     handlers::CallbackDrop,
+    key_expr::{keyexpr, OwnedKeyExpr},
     liveliness::LivelinessToken,
     matching::MatchingListener,
     pubsub::{Publisher, Subscriber},
     qos::Reliability,
     query::{Querier, Query, Queryable, Reply, Selector},
-    session::{Link, LinkEventsListener, Transport, TransportEventsListener},
+    session::{EntityGlobalId, Link, LinkEventsListener, Transport, TransportEventsListener},
     Session,
 };
+use zenoh_ext::{
+    AdvancedPublisher, AdvancedPublisherBuilderExt, AdvancedSubscriber,
+    AdvancedSubscriberBuilderExt, CacheConfig, HistoryConfig, MissDetectionConfig, RecoveryConfig,
+};
 use zenoh_result::bail;
 
 use crate::{
+    acl::ClientAcl,
+    commands::RevokedEntity,
+    config::{AccessControlAction, Permission},
     interface::{
-        self, DeclareLinkEventsListener, DeclareLivelinessSubscriber, DeclareLivelinessToken,
-        DeclarePublisher, DeclareQuerier, DeclareQueryable, DeclareSubscriber,
-        DeclareTransportEventsListener, Delete, Get, LinkEventsListenerId, LinkInfoWire,
-        LivelinessGet, LivelinessTokenId, MatchingListenerId, MatchingStatus, PingAck,
-        PublisherDeclareMatchingListener, PublisherDelete, PublisherGetMatchingStatus, PublisherId,
-        PublisherPut, Put, QuerierDeclareMatchingListener, QuerierGet, QuerierGetMatchingStatus,
-        QuerierId, QueryId, QueryResponseFinal, QueryableId, ReplyDel, ReplyErr, ReplyOk,
-        ResponseLinks, ResponseSessionInfo, ResponseTimestamp, ResponseTransports, SubscriberId,
+        self, DeclareAdvancedPublisher, DeclareAdvancedSubscriber, DeclareLinkEventsListener,
+        DeclareLivelinessSubscriber, DeclareLivelinessToken, DeclarePublisher, DeclareQuerier,
+        DeclareQueryable, DeclareSubscriber, DeclareTransportEventsListener, Delete, Get,
+        Heartbeat, LinkEventsListenerId, LinkInfoWire, LivelinessGet, LivelinessTokenId,
+        MatchingListenerId, MatchingStatus, PingAck, PublisherDeclareMatchingListener,
+        PublisherDelete, PublisherGetMatchingStatus, PublisherId, PublisherPut, Put,
+        QuerierDeclareMatchingListener, QuerierGet, QuerierGetMatchingStatus, QuerierId, QueryId,
+        QueryResponseFinal, QueryableId, Recovery, ReplyDel, ReplyErr, ReplyOk, ResponseLinks,
+        ResponseSessionInfo, ResponseTimestamp, ResponseTransports, SubscriberId,
         TransportEventsListenerId, TransportInfoWire, UndeclareLinkEventsListener,
         UndeclareLivelinessSubscriber, UndeclareLivelinessToken, UndeclareMatchingListener,
         UndeclarePublisher, UndeclareQuerier, UndeclareQueryable, UndeclareSubscriber,
         UndeclareTransportEventsListener,
     },
-    AdminSpaceClient, InRemoteMessage, OutRemoteMessage, SequenceId,
+    outbound::OutboundSender,
+    protocol::LEGACY_PROTOCOL_VERSION,
+    quotas::{ClientQuotas, EntityKind},
+    ratelimit::{ClientRateLimiter, RateLimited},
+    AdminSpaceClient, AdminSpaceEntity, InRemoteMessage, OutRemoteMessage,
 };
 
 // Since we do not have api to get query timeout
@@ -65,11 +77,13 @@ use crate::{
 const MAX_NUM_PENDING_QUERIES: usize = 1000;
 pub(crate) struct RemoteState {
     id: String,
-    tx: Sender<(OutRemoteMessage, Option<SequenceId>)>,
+    tx: OutboundSender,
     admin_client: Arc<Mutex<AdminSpaceClient>>,
     session: Session,
     subscribers: HashMap<SubscriberId, Subscriber<()>>,
+    advanced_subscribers: HashMap<SubscriberId, AdvancedSubscriber<()>>,
     publishers: HashMap<PublisherId, Publisher<'static>>,
+    advanced_publishers: HashMap<PublisherId, AdvancedPublisher<'static>>,
     queryables: HashMap<QueryableId, Queryable<()>>,
     pending_queries: Arc<Mutex<LruCache<QueryId, Query>>>,
     query_counter: Arc<AtomicU32>,
@@ -79,13 +93,39 @@ pub(crate) struct RemoteState {
     matching_listeners: HashMap<MatchingListenerId, MatchingListener<()>>,
     transport_events_listeners: HashMap<TransportEventsListenerId, TransportEventsListener<()>>,
     link_events_listeners: HashMap<LinkEventsListenerId, LinkEventsListener<()>>,
+    /// Announces the client to the other zenoh nodes.
+    presence_token: Option<LivelinessToken>,
+    acl: ClientAcl,
+    quotas: ClientQuotas,
+    rate_limiter: ClientRateLimiter,
+    /// The version of the protocol negotiated with the client.
+    protocol_version: u32,
+}
+
+/// Forwards to the client the samples of a publisher which its advanced subscriber missed.
+fn forward_sample_miss(
+    tx: &OutboundSender,
+    subscriber_id: SubscriberId,
+    source: EntityGlobalId,
+    nb: u32,
+) {
+    let msg = interface::SampleMiss {
+        subscriber_id,
+        source_zid: source.zid(),
+        source_eid: source.eid(),
+        nb,
+    };
+    let _ = tx.send((OutRemoteMessage::SampleMiss(msg), None));
 }
 
 impl RemoteState {
     pub(crate) fn new(
-        tx: Sender<(OutRemoteMessage, Option<SequenceId>)>,
+        tx: OutboundSender,
         admin_client: Arc<Mutex<AdminSpaceClient>>,
         session: Session,
+        acl: ClientAcl,
+        quotas: ClientQuotas,
+        rate_limiter: ClientRateLimiter,
     ) -> Self {
         let id = admin_client.lock().unwrap().id().to_string();
         tracing::trace!("RemoteState::new: id={}", id);
@@ -95,7 +135,9 @@ impl RemoteState {
             admin_client,
             session,
             subscribers: HashMap::new(),
+            advanced_subscribers: HashMap::new(),
             publishers: HashMap::new(),
+            advanced_publishers: HashMap::new(),
             queryables: HashMap::new(),
             pending_queries: Arc::new(Mutex::new(LruCache::new(
                 MAX_NUM_PENDING_QUERIES.try_into().unwrap(),
@@ -107,6 +149,24 @@ impl RemoteState {
             matching_listeners: HashMap::new(),
             transport_events_listeners: HashMap::new(),
             link_events_listeners: HashMap::new(),
+            presence_token: None,
+            acl,
+            quotas,
+            rate_limiter,
+            protocol_version: LEGACY_PROTOCOL_VERSION,
+        }
+    }
+
+    pub(crate) fn set_protocol_version(&mut self, version: u32) {
+        self.protocol_version = version;
+    }
+
+    /// Declares a liveliness token announcing the client until its session ends.
+    pub(crate) async fn declare_presence_token(&mut self, key_expr: OwnedKeyExpr) {
+        tracing::trace!("declare_presence_token: {}", key_expr);
+        match self.session.liveliness().declare_token(key_expr).await {
+            Ok(token) => self.presence_token = Some(token),
+            Err(e) => tracing::error!("Client {}: {}", self.id, e),
         }
     }
 
@@ -120,6 +180,14 @@ impl RemoteState {
             }
         }
 
+        let mut advanced_publishers = HashMap::new();
+        std::mem::swap(&mut advanced_publishers, &mut self.advanced_publishers);
+        for (_, publisher) in advanced_publishers {
+            if let Err(e) = publisher.undeclare().await {
+                tracing::error!("{e}")
+            }
+        }
+
         let mut subscribers = HashMap::new();
         std::mem::swap(&mut subscribers, &mut self.subscribers);
 
@@ -129,6 +197,14 @@ impl RemoteState {
             }
         }
 
+        let mut advanced_subscribers = HashMap::new();
+        std::mem::swap(&mut advanced_subscribers, &mut self.advanced_subscribers);
+        for (_, subscriber) in advanced_subscribers {
+            if let Err(e) = subscriber.undeclare().await {
+                tracing::error!("{e}")
+            }
+        }
+
         let mut queryables = HashMap::new();
         std::mem::swap(&mut queryables, &mut self.queryables);
         for (_, queryable) in queryables {
@@ -138,6 +214,7 @@ impl RemoteState {
         }
 
         self.pending_queries.lock().as_mut().unwrap().clear();
+        self.admin_client.lock().unwrap().clear_pending_queries();
 
         let mut liveliness_tokens = HashMap::new();
         std::mem::swap(&mut liveliness_tokens, &mut self.liveliness_tokens);
@@ -177,6 +254,12 @@ impl RemoteState {
             }
         }
 
+        if let Some(token) = self.presence_token.take() {
+            if let Err(e) = token.undeclare().await {
+                tracing::error!("{e}")
+            }
+        }
+
         if let Err(err) = self.session.close().await {
             tracing::error!("{err}")
         };
@@ -192,12 +275,15 @@ impl RemoteState {
             declare_publisher.id,
             declare_publisher.keyexpr
         );
-        if self.publishers.contains_key(&declare_publisher.id) {
+        if self.publisher_exists(&declare_publisher.id) {
             bail!(
                 "Publisher with id: '{}' already exists",
                 declare_publisher.id
             );
         }
+        let entity = AdminSpaceEntity::new(Some(declare_publisher.keyexpr.as_str()))
+            .qos(&declare_publisher.qos)
+            .setting("encoding", declare_publisher.encoding.to_string());
         let publisher = self
             .session
             .declare_publisher(declare_publisher.keyexpr)
@@ -211,7 +297,7 @@ impl RemoteState {
         self.admin_client
             .lock()
             .unwrap()
-            .register_publisher(declare_publisher.id, publisher.key_expr().as_str());
+            .register_publisher(declare_publisher.id, entity);
         self.publishers.insert(declare_publisher.id, publisher);
         tracing::trace!(
             "declare_publisher: id={} completed successfully",
@@ -220,29 +306,96 @@ impl RemoteState {
         Ok(None)
     }
 
+    async fn declare_advanced_publisher(
+        &mut self,
+        msg: DeclareAdvancedPublisher,
+    ) -> Result<Option<OutRemoteMessage>, zenoh_result::Error> {
+        tracing::trace!(
+            "declare_advanced_publisher: id={}, keyexpr={}",
+            msg.id,
+            msg.keyexpr
+        );
+        if self.publisher_exists(&msg.id) {
+            bail!("Publisher with id: '{}' already exists", msg.id);
+        }
+        let entity = AdminSpaceEntity::new(Some(msg.keyexpr.as_str()))
+            .qos(&msg.qos)
+            .setting("encoding", msg.encoding.to_string())
+            .setting("advanced", true)
+            .setting("cache_max_samples", msg.cache_max_samples)
+            .setting("sample_miss_detection", msg.sample_miss_detection.is_some())
+            .setting("publisher_detection", msg.publisher_detection);
+        let mut builder = self
+            .session
+            .declare_publisher(msg.keyexpr)
+            .encoding(msg.encoding)
+            .priority(msg.qos.priority())
+            .congestion_control(msg.qos.congestion_control())
+            .express(msg.qos.express())
+            .allowed_destination(msg.qos.locality())
+            .reliability(msg.qos.reliability())
+            .advanced();
+        if let Some(max_samples) = msg.cache_max_samples {
+            builder = builder.cache(CacheConfig::default().max_samples(max_samples as usize));
+        }
+        if let Some(heartbeat) = msg.sample_miss_detection {
+            let config = match heartbeat {
+                Heartbeat::None => MissDetectionConfig::default(),
+                Heartbeat::Periodic(period) => MissDetectionConfig::default().heartbeat(period),
+                Heartbeat::Sporadic(period) => {
+                    MissDetectionConfig::default().sporadic_heartbeat(period)
+                }
+            };
+            builder = builder.sample_miss_detection(config);
+        }
+        if msg.publisher_detection {
+            builder = builder.publisher_detection();
+        }
+        if let Some(metadata) = msg.publisher_detection_metadata {
+            builder = builder.publisher_detection_metadata(metadata);
+        }
+        let publisher = builder.await?;
+        self.admin_client
+            .lock()
+            .unwrap()
+            .register_publisher(msg.id, entity);
+        self.advanced_publishers.insert(msg.id, publisher);
+        tracing::trace!(
+            "declare_advanced_publisher: id={} completed successfully",
+            msg.id
+        );
+        Ok(None)
+    }
+
+    /// Whether a publisher, plain or advanced, was declared with the id.
+    fn publisher_exists(&self, id: &PublisherId) -> bool {
+        self.publishers.contains_key(id) || self.advanced_publishers.contains_key(id)
+    }
+
     async fn undeclare_publisher(
         &mut self,
         undeclare_publisher: UndeclarePublisher,
     ) -> Result<Option<OutRemoteMessage>, zenoh_result::Error> {
         tracing::trace!("undeclare_publisher: id={}", undeclare_publisher.id);
-        match self.publishers.remove(&undeclare_publisher.id) {
-            Some(p) => {
-                p.undeclare().await?;
-                self.admin_client
-                    .lock()
-                    .unwrap()
-                    .unregister_publisher(undeclare_publisher.id);
-                tracing::trace!(
-                    "undeclare_publisher: id={} completed successfully",
-                    undeclare_publisher.id
-                );
-                Ok(None)
-            }
-            None => bail!(
+        if let Some(p) = self.publishers.remove(&undeclare_publisher.id) {
+            p.undeclare().await?;
+        } else if let Some(p) = self.advanced_publishers.remove(&undeclare_publisher.id) {
+            p.undeclare().await?;
+        } else {
+            bail!(
                 "Publisher with id {} does not exist",
                 undeclare_publisher.id
-            ),
+            );
         }
+        self.admin_client
+            .lock()
+            .unwrap()
+            .unregister_publisher(undeclare_publisher.id);
+        tracing::trace!(
+            "undeclare_publisher: id={} completed successfully",
+            undeclare_publisher.id
+        );
+        Ok(None)
     }
 
     async fn declare_subscriber(
@@ -254,12 +407,14 @@ impl RemoteState {
             declare_subscriber.id,
             declare_subscriber.keyexpr
         );
-        if self.subscribers.contains_key(&declare_subscriber.id) {
+        if self.subscriber_exists(&declare_subscriber.id) {
             bail!(
                 "Subscriber with id: '{}' already exists",
                 declare_subscriber.id
             );
         }
+        let entity = AdminSpaceEntity::new(Some(declare_subscriber.keyexpr.as_str()))
+            .locality(declare_subscriber.allowed_origin);
         let tx = self.tx.clone();
         let subscriber = self
             .session
@@ -276,7 +431,7 @@ impl RemoteState {
         self.admin_client
             .lock()
             .unwrap()
-            .register_subscriber(declare_subscriber.id, subscriber.key_expr().as_str());
+            .register_subscriber(declare_subscriber.id, entity);
         self.subscribers.insert(declare_subscriber.id, subscriber);
         tracing::trace!(
             "declare_subscriber: id={} completed successfully",
@@ -285,29 +440,113 @@ impl RemoteState {
         Ok(None)
     }
 
+    async fn declare_advanced_subscriber(
+        &mut self,
+        msg: DeclareAdvancedSubscriber,
+    ) -> Result<Option<OutRemoteMessage>, zenoh_result::Error> {
+        tracing::trace!(
+            "declare_advanced_subscriber: id={}, keyexpr={}",
+            msg.id,
+            msg.keyexpr
+        );
+        if self.subscriber_exists(&msg.id) {
+            bail!("Subscriber with id: '{}' already exists", msg.id);
+        }
+        let entity = AdminSpaceEntity::new(Some(msg.keyexpr.as_str()))
+            .locality(msg.allowed_origin)
+            .setting("advanced", true)
+            .setting("history", msg.history.is_some())
+            .setting("recovery", msg.recovery.is_some())
+            .setting("subscriber_detection", msg.subscriber_detection);
+        let tx = self.tx.clone();
+        let mut builder = self
+            .session
+            .declare_subscriber(msg.keyexpr)
+            .allowed_origin(msg.allowed_origin)
+            .advanced()
+            .callback(move |s| {
+                let msg = interface::Sample {
+                    subscriber_id: msg.id,
+                    sample: s,
+                };
+                let _ = tx.send((OutRemoteMessage::Sample(msg), None));
+            });
+        if let Some(history) = msg.history {
+            let mut config = HistoryConfig::default();
+            if history.detect_late_publishers {
+                config = config.detect_late_publishers();
+            }
+            if let Some(max_samples) = history.max_samples {
+                config = config.max_samples(max_samples as usize);
+            }
+            if let Some(max_age_ms) = history.max_age_ms {
+                config = config.max_age(max_age_ms as f64 / 1000.0);
+            }
+            builder = builder.history(config);
+        }
+        if let Some(recovery) = msg.recovery {
+            let config = match recovery {
+                Recovery::Heartbeat => RecoveryConfig::default().heartbeat(),
+                Recovery::PeriodicQueries(period) => {
+                    RecoveryConfig::default().periodic_queries(period)
+                }
+            };
+            builder = builder.recovery(config);
+        }
+        if let Some(query_timeout_ms) = msg.query_timeout_ms {
+            builder = builder.query_timeout(Duration::from_millis(query_timeout_ms));
+        }
+        if msg.subscriber_detection {
+            builder = builder.subscriber_detection();
+        }
+        let subscriber = builder.await?;
+        let tx = self.tx.clone();
+        subscriber
+            .sample_miss_listener()
+            .callback(move |miss| forward_sample_miss(&tx, msg.id, miss.source(), miss.nb()))
+            .background()
+            .await?;
+        self.admin_client
+            .lock()
+            .unwrap()
+            .register_subscriber(msg.id, entity);
+        self.advanced_subscribers.insert(msg.id, subscriber);
+        tracing::trace!(
+            "declare_advanced_subscriber: id={} completed successfully",
+            msg.id
+        );
+        Ok(None)
+    }
+
+    /// Whether a subscriber, plain or advanced, was declared with the id.
+    fn subscriber_exists(&self, id: &SubscriberId) -> bool {
+        self.subscribers.contains_key(id) || self.advanced_subscribers.contains_key(id)
+    }
+
     async fn undeclare_subscriber(
         &mut self,
         undeclare_subscriber: UndeclareSubscriber,
     ) -> Result<Option<OutRemoteMessage>, zenoh_result::Error> {
         tracing::trace!("undeclare_subscriber: id={}", undeclare_subscriber.id);
-        match self.subscribers.remove(&undeclare_subscriber.id) {
-            Some(s) => {
-                s.undeclare().await?;
-                self.admin_client
-                    .lock()
-                    .unwrap()
-                    .unregister_subscriber(undeclare_subscriber.id);
-                tracing::trace!(
-                    "undeclare_subscriber: id={} completed successfully",
-                    undeclare_subscriber.id
-                );
-                Ok(None)
-            }
-            None => bail!(
+        if let Some(s) = self.subscribers.remove(&undeclare_subscriber.id) {
+            s.undeclare().await?;
+        } else if let Some(s) = self.advanced_subscribers.remove(&undeclare_subscriber.id) {
+            s.undeclare().await?;
+        } else {
+            bail!(
                 "Subscriber with id {} does not exist",
                 undeclare_subscriber.id
-            ),
+            );
         }
+        self.admin_client
+            .lock()
+            .unwrap()
+            .unregister_subscriber(undeclare_subscriber.id);
+        tracing::trace!(
+            "undeclare_subscriber: id={} completed successfully",
+            undeclare_subscriber.id
+        );
+        Ok(None)
     }
 
     async fn declare_queryable(
@@ -325,9 +564,13 @@ impl RemoteState {
                 declare_queryable.id
             );
         }
+        let entity = AdminSpaceEntity::new(Some(declare_queryable.keyexpr.as_str()))
+            .locality(declare_queryable.allowed_origin)
+            .setting("complete", declare_queryable.complete);
         let tx = self.tx.clone();
         let query_counter = self.query_counter.clone();
         let pending_queries = self.pending_queries.clone();
+        let admin_client = self.admin_client.clone();
 
         let queryable = self
             .session
@@ -342,14 +585,23 @@ impl RemoteState {
                     query_id,
                     query: q.clone(),
                 };
-                pending_queries.lock().unwrap().put(query_id, q);
+                let entity = AdminSpaceEntity::new(Some(q.key_expr().as_str()))
+                    .setting("parameters", q.parameters().as_str())
+                    .setting("queryable_id", declare_queryable.id.0);
+                let evicted = pending_queries.lock().unwrap().push(query_id, q);
+                let mut admin_client = admin_client.lock().unwrap();
+                if let Some((evicted, _)) = evicted {
+                    admin_client.unregister_pending_query(evicted);
+                }
+                admin_client.register_pending_query(query_id, entity);
+                drop(admin_client);
                 let _ = tx.send((OutRemoteMessage::Query(msg), None));
             })
             .await?;
         self.admin_client
             .lock()
             .unwrap()
-            .register_queryable(declare_queryable.id, queryable.key_expr().as_str());
+            .register_queryable(declare_queryable.id, entity);
         self.queryables.insert(declare_queryable.id, queryable);
         tracing::trace!(
             "declare_queryable: id={} completed successfully",
@@ -395,6 +647,17 @@ impl RemoteState {
         if self.queriers.contains_key(&declare_querier.id) {
             bail!("Querier with id: '{}' already exists", declare_querier.id);
         }
+        let entity = AdminSpaceEntity::new(Some(declare_querier.keyexpr.as_str()))
+            .qos(&declare_querier.qos)
+            .setting(
+                "target",
+                format!("{:?}", declare_querier.query_settings.target()),
+            )
+            .setting(
+                "consolidation",
+                format!("{:?}", declare_querier.query_settings.consolidation()),
+            )
+            .setting("timeout_ms", declare_querier.timeout_ms);
         let querier = self
             .session
             .declare_querier(declare_querier.keyexpr)
@@ -410,7 +673,7 @@ impl RemoteState {
         self.admin_client
             .lock()
             .unwrap()
-            .register_querier(declare_querier.id, querier.key_expr().as_str());
+            .register_querier(declare_querier.id, entity);
         self.queriers.insert(declare_querier.id, querier);
         tracing::trace!(
             "declare_querier: id={} completed successfully",
@@ -493,29 +756,35 @@ impl RemoteState {
 
     async fn publisher_put(&self, publisher_put: PublisherPut) -> Result<(), zenoh_result::Error> {
         tracing::trace!("publisher_put: publisher_id={}", publisher_put.publisher_id);
-        match self.publishers.get(&publisher_put.publisher_id) {
-            Some(p) => {
-                let mut pb = p
-                    .put(publisher_put.payload)
-                    .attachment(publisher_put.attachment)
-                    .timestamp(publisher_put.timestamp);
-                if let Some(encoding) = publisher_put.encoding {
-                    pb = pb.encoding(encoding);
-                }
-                pb.await?;
-                tracing::trace!(
-                    "publisher_put: publisher_id={} completed successfully",
-                    publisher_put.publisher_id
-                );
-                Ok(())
-            }
-            None => {
-                bail!(
-                    "Publisher with id {} does not exist",
-                    publisher_put.publisher_id
-                );
-            }
+        if let Some(p) = self.publishers.get(&publisher_put.publisher_id) {
+            let mut pb = p
+                .put(publisher_put.payload)
+                .attachment(publisher_put.attachment)
+                .timestamp(publisher_put.timestamp);
+            if let Some(encoding) = publisher_put.encoding {
+                pb = pb.encoding(encoding);
+            }
+            pb.await?;
+        } else if let Some(p) = self.advanced_publishers.get(&publisher_put.publisher_id) {
+            let mut pb = p
+                .put(publisher_put.payload)
+                .attachment(publisher_put.attachment)
+                .timestamp(publisher_put.timestamp);
+            if let Some(encoding) = publisher_put.encoding {
+                pb = pb.encoding(encoding);
+            }
+            pb.await?;
+        } else {
+            bail!(
+                "Publisher with id {} does not exist",
+                publisher_put.publisher_id
+            );
         }
+        tracing::trace!(
+            "publisher_put: publisher_id={} completed successfully",
+            publisher_put.publisher_id
+        );
+        Ok(())
     }
 
     async fn publisher_delete(
@@ -526,25 +795,27 @@ impl RemoteState {
             "publisher_delete: publisher_id={}",
             publisher_delete.publisher_id
         );
-        match self.publishers.get(&publisher_delete.publisher_id) {
-            Some(p) => {
-                p.delete()
-                    .attachment(publisher_delete.attachment)
-                    .timestamp(publisher_delete.timestamp)
-                    .await?;
-                tracing::trace!(
-                    "publisher_delete: publisher_id={} completed successfully",
-                    publisher_delete.publisher_id
-                );
-                Ok(())
-            }
-            None => {
-                bail!(
-                    "Publisher with id {} does not exist",
-                    publisher_delete.publisher_id
-                );
-            }
+        if let Some(p) = self.publishers.get(&publisher_delete.publisher_id) {
+            p.delete()
+                .attachment(publisher_delete.attachment)
+                .timestamp(publisher_delete.timestamp)
+                .await?;
+        } else if let Some(p) = self.advanced_publishers.get(&publisher_delete.publisher_id) {
+            p.delete()
+                .attachment(publisher_delete.attachment)
+                .timestamp(publisher_delete.timestamp)
+                .await?;
+        } else {
+            bail!(
+                "Publisher with id {} does not exist",
+                publisher_delete.publisher_id
+            );
         }
+        tracing::trace!(
+            "publisher_delete: publisher_id={} completed successfully",
+            publisher_delete.publisher_id
+        );
+        Ok(())
     }
     fn create_get_callback(&self, query_id: QueryId) -> CallbackDrop<impl Fn(Reply), impl FnMut()> {
         let tx1 = self.tx.clone();
@@ -734,11 +1005,16 @@ impl RemoteState {
                 declare_liveliness_token.id
             );
         }
+        let entity = AdminSpaceEntity::new(Some(declare_liveliness_token.keyexpr.as_str()));
         let token = self
             .session
             .liveliness()
             .declare_token(declare_liveliness_token.keyexpr)
             .await?;
+        self.admin_client
+            .lock()
+            .unwrap()
+            .register_liveliness_token(declare_liveliness_token.id, entity);
         self.liveliness_tokens
             .insert(declare_liveliness_token.id, token);
         tracing::trace!(
@@ -762,6 +1038,10 @@ impl RemoteState {
         {
             Some(t) => {
                 t.undeclare().await?;
+                self.admin_client
+                    .lock()
+                    .unwrap()
+                    .unregister_liveliness_token(undeclare_liveliness_token.id);
                 tracing::trace!(
                     "undeclare_liveliness_token: id={} completed successfully",
                     undeclare_liveliness_token.id
@@ -793,6 +1073,8 @@ impl RemoteState {
                 declare_liveliness_subscriber.id
             );
         }
+        let entity = AdminSpaceEntity::new(Some(declare_liveliness_subscriber.keyexpr.as_str()))
+            .setting("history", declare_liveliness_subscriber.history);
         let tx = self.tx.clone();
         let subscriber = self
             .session
@@ -807,6 +1089,10 @@ impl RemoteState {
                 let _ = tx.send((OutRemoteMessage::Sample(msg), None));
             })
             .await?;
+        self.admin_client
+            .lock()
+            .unwrap()
+            .register_liveliness_subscriber(declare_liveliness_subscriber.id, entity);
         self.liveliness_subscribers
             .insert(declare_liveliness_subscriber.id, subscriber);
         tracing::trace!(
@@ -830,6 +1116,10 @@ impl RemoteState {
         {
             Some(t) => {
                 t.undeclare().await?;
+                self.admin_client
+                    .lock()
+                    .unwrap()
+                    .unregister_liveliness_subscriber(undeclare_liveliness_subscriber.id);
                 tracing::trace!(
                     "undeclare_liveliness_subscriber: id={} completed successfully",
                     undeclare_liveliness_subscriber.id
@@ -877,6 +1167,10 @@ impl RemoteState {
             .pop(&response_final.query_id)
         {
             Some(_) => {
+                self.admin_client
+                    .lock()
+                    .unwrap()
+                    .unregister_pending_query(response_final.query_id);
                 tracing::trace!(
                     "response_final: query_id={} completed successfully",
                     response_final.query_id
@@ -896,32 +1190,45 @@ impl RemoteState {
             msg.id,
             msg.publisher_id
         );
-        match self.publishers.get(&msg.publisher_id) {
-            Some(publisher) => {
-                if self.matching_listeners.contains_key(&msg.id) {
-                    bail!("Matching listener with id: '{}' already exists", msg.id);
-                }
-                let tx = self.tx.clone();
-                let ml = publisher
-                    .matching_listener()
-                    .callback(move |matching_status| {
-                        let msg = interface::MatchingStatusUpdate {
-                            matching_listener_id: msg.id,
-                            matching: matching_status.matching(),
-                        };
-                        let _ = tx.send((OutRemoteMessage::MatchingStatusUpdate(msg), None));
-                    })
-                    .await?;
-                self.matching_listeners.insert(msg.id, ml);
-                tracing::trace!(
-                    "publisher_declare_matching_listener: id={} completed successfully",
-                    msg.id
-                );
-            }
-            None => {
-                bail!("Publisher with id: '{}' does not exist", msg.publisher_id);
-            }
+        if !self.publisher_exists(&msg.publisher_id) {
+            bail!("Publisher with id: '{}' does not exist", msg.publisher_id);
+        }
+        if self.matching_listeners.contains_key(&msg.id) {
+            bail!("Matching listener with id: '{}' already exists", msg.id);
+        }
+        let tx = self.tx.clone();
+        let callback = move |matching_status: zenoh::matching::MatchingStatus| {
+            let msg = interface::MatchingStatusUpdate {
+                matching_listener_id: msg.id,
+                matching: matching_status.matching(),
+            };
+            let _ = tx.send((OutRemoteMessage::MatchingStatusUpdate(msg), None));
+        };
+        let (ml, key_expr) = match self.publishers.get(&msg.publisher_id) {
+            Some(publisher) => (
+                publisher.matching_listener().callback(callback).await?,
+                publisher.key_expr().to_string(),
+            ),
+            None => match self.advanced_publishers.get(&msg.publisher_id) {
+                Some(publisher) => (
+                    publisher.matching_listener().callback(callback).await?,
+                    publisher.key_expr().to_string(),
+                ),
+                None => bail!("Publisher with id: '{}' does not exist", msg.publisher_id),
+            },
         };
+        self.matching_listeners.insert(msg.id, ml);
+        self.admin_client
+            .lock()
+            .unwrap()
+            .register_matching_listener(
+                msg.id,
+                AdminSpaceEntity::new(Some(&key_expr)).setting("publisher_id", msg.publisher_id.0),
+            );
+        tracing::trace!(
+            "publisher_declare_matching_listener: id={} completed successfully",
+            msg.id
+        );
         Ok(None)
     }
 
@@ -933,6 +1240,10 @@ impl RemoteState {
         match self.matching_listeners.remove(&msg.id) {
             Some(ml) => {
                 ml.undeclare().await?;
+                self.admin_client
+                    .lock()
+                    .unwrap()
+                    .unregister_matching_listener(msg.id);
                 tracing::trace!(
                     "undeclare_matching_listener: id={} completed successfully",
                     msg.id
@@ -951,9 +1262,15 @@ impl RemoteState {
             "publisher_get_matching_status: publisher_id={}",
             msg.publisher_id
         );
-        match self.publishers.get(&msg.publisher_id) {
-            Some(p) => {
-                let status = p.matching_status().await?;
+        let status = match self.publishers.get(&msg.publisher_id) {
+            Some(p) => Some(p.matching_status().await?),
+            None => match self.advanced_publishers.get(&msg.publisher_id) {
+                Some(p) => Some(p.matching_status().await?),
+                None => None,
+            },
+        };
+        match status {
+            Some(status) => {
                 tracing::trace!(
                     "publisher_get_matching_status: publisher_id={} completed successfully",
                     msg.publisher_id
@@ -992,6 +1309,14 @@ impl RemoteState {
                     })
                     .await?;
                 self.matching_listeners.insert(msg.id, ml);
+                self.admin_client
+                    .lock()
+                    .unwrap()
+                    .register_matching_listener(
+                        msg.id,
+                        AdminSpaceEntity::new(Some(querier.key_expr().as_str()))
+                            .setting("querier_id", msg.querier_id.0),
+                    );
                 tracing::trace!(
                     "querier_declare_matching_listener: id={} completed successfully",
                     msg.id
@@ -1110,6 +1435,13 @@ impl RemoteState {
                 let _ = tx.send((OutRemoteMessage::TransportEventUpdate(update), None));
             })
             .await?;
+        self.admin_client
+            .lock()
+            .unwrap()
+            .register_transport_events_listener(
+                msg.id,
+                AdminSpaceEntity::new(None).setting("history", msg.history),
+            );
         self.transport_events_listeners.insert(msg.id, listener);
         tracing::trace!(
             "declare_transport_events_listener: id={} completed successfully",
@@ -1126,6 +1458,10 @@ impl RemoteState {
         match self.transport_events_listeners.remove(&msg.id) {
             Some(listener) => {
                 listener.undeclare().await?;
+                self.admin_client
+                    .lock()
+                    .unwrap()
+                    .unregister_transport_events_listener(msg.id);
                 tracing::trace!(
                     "undeclare_transport_events_listener: id={} completed successfully",
                     msg.id
@@ -1167,6 +1503,13 @@ impl RemoteState {
                 let _ = tx.send((OutRemoteMessage::LinkEventUpdate(update), None));
             })
             .await?;
+        self.admin_client
+            .lock()
+            .unwrap()
+            .register_link_events_listener(
+                msg.id,
+                AdminSpaceEntity::new(None).setting("history", msg.history),
+            );
         self.link_events_listeners.insert(msg.id, listener);
         tracing::trace!(
             "declare_link_events_listener: id={} completed successfully",
@@ -1183,6 +1526,10 @@ impl RemoteState {
         match self.link_events_listeners.remove(&msg.id) {
             Some(listener) => {
                 listener.undeclare().await?;
+                self.admin_client
+                    .lock()
+                    .unwrap()
+                    .unregister_link_events_listener(msg.id);
                 tracing::trace!(
                     "undeclare_link_events_listener: id={} completed successfully",
                     msg.id
@@ -1193,11 +1540,150 @@ impl RemoteState {
         }
     }
 
+    /// Returns the error to send back to the client if the message is denied by the ACL.
+    fn check_access(&self, msg: &InRemoteMessage) -> Option<OutRemoteMessage> {
+        let (action, key_expr) = match msg {
+            InRemoteMessage::DeclarePublisher(m) => (AccessControlAction::Publish, &m.keyexpr),
+            InRemoteMessage::DeclareAdvancedPublisher(m) => {
+                (AccessControlAction::Publish, &m.keyexpr)
+            }
+            InRemoteMessage::Put(m) => (AccessControlAction::Publish, &m.keyexpr),
+            InRemoteMessage::Delete(m) => (AccessControlAction::Publish, &m.keyexpr),
+            InRemoteMessage::DeclareSubscriber(m) => (AccessControlAction::Subscribe, &m.keyexpr),
+            InRemoteMessage::DeclareAdvancedSubscriber(m) => {
+                (AccessControlAction::Subscribe, &m.keyexpr)
+            }
+            InRemoteMessage::Get(m) => (AccessControlAction::Query, &m.keyexpr),
+            InRemoteMessage::DeclareQuerier(m) => (AccessControlAction::Query, &m.keyexpr),
+            InRemoteMessage::DeclareQueryable(m) => {
+                (AccessControlAction::DeclareQueryable, &m.keyexpr)
+            }
+            InRemoteMessage::DeclareLivelinessToken(m) => {
+                (AccessControlAction::DeclareLivelinessToken, &m.keyexpr)
+            }
+            InRemoteMessage::DeclareLivelinessSubscriber(m) => {
+                (AccessControlAction::Subscribe, &m.keyexpr)
+            }
+            InRemoteMessage::LivelinessGet(m) => (AccessControlAction::Query, &m.keyexpr),
+            _ => return None,
+        };
+        match self.acl.permission(action, key_expr) {
+            Permission::Allow => None,
+            Permission::Deny => {
+                tracing::warn!(
+                    "Client {}: access denied to {} on '{}'",
+                    self.id,
+                    action,
+                    key_expr
+                );
+                Some(OutRemoteMessage::Error(interface::Error {
+                    error: format!("Access denied: not allowed to {action} on '{key_expr}'"),
+                }))
+            }
+        }
+    }
+
+    /// Applies the rate limits to the publications and queries of the client. Either waits
+    /// for the limits to allow the message, which slows down the reading of the WebSocket,
+    /// or returns the error to send back to the client.
+    async fn check_rate(&mut self, msg: &InRemoteMessage) -> Option<OutRemoteMessage> {
+        let key_expr: &keyexpr = match msg {
+            InRemoteMessage::Put(m) => &m.keyexpr,
+            InRemoteMessage::Delete(m) => &m.keyexpr,
+            InRemoteMessage::PublisherPut(PublisherPut { publisher_id, .. })
+            | InRemoteMessage::PublisherDelete(PublisherDelete { publisher_id, .. }) => {
+                match self.publishers.get(publisher_id) {
+                    Some(p) => p.key_expr(),
+                    None => self.advanced_publishers.get(publisher_id)?.key_expr(),
+                }
+            }
+            InRemoteMessage::Get(m) => &m.keyexpr,
+            InRemoteMessage::QuerierGet(m) => self.queriers.get(&m.querier_id)?.key_expr(),
+            _ => return None,
+        };
+        let bytes = msg.payload_size() + msg.attachment_size();
+        loop {
+            match self.rate_limiter.acquire(key_expr, bytes, Instant::now()) {
+                Ok(()) => return None,
+                Err(RateLimited::Wait(wait)) => tokio::time::sleep(wait).await,
+                Err(RateLimited::Rejected) => {
+                    tracing::warn!("Client {}: rate limit exceeded on '{}'", self.id, key_expr);
+                    return Some(OutRemoteMessage::Error(interface::Error {
+                        error: format!("Rate limit exceeded on '{key_expr}'"),
+                    }));
+                }
+            }
+        }
+    }
+
     pub(crate) async fn handle_message(
         &mut self,
         msg: InRemoteMessage,
     ) -> Result<Option<OutRemoteMessage>, zenoh_result::Error> {
         tracing::trace!("handle_message: {:?}", std::mem::discriminant(&msg));
+        if msg.protocol_version() > self.protocol_version {
+            return Ok(Some(OutRemoteMessage::Error(interface::Error {
+                error: format!(
+                    "The message requires protocol version {}, version {} was negotiated",
+                    msg.protocol_version(),
+                    self.protocol_version
+                ),
+            })));
+        }
+        if let Some(error) = self.check_access(&msg) {
+            return Ok(Some(error));
+        }
+        if let Some(error) = self.check_rate(&msg).await {
+            return Ok(Some(error));
+        }
+        let declared = EntityKind::declared_by(&msg);
+        let undeclared = EntityKind::undeclared_by(&msg);
+        if let Some(kind) = declared {
+            if let Err(error) = self.quotas.acquire(kind) {
+                tracing::warn!("Client {}: {}", self.id, error);
+                return Ok(Some(OutRemoteMessage::Error(interface::Error {
+                    error: error.to_string(),
+                })));
+            }
+        }
+        let result = self.execute(msg).await;
+        match (declared, undeclared, &result) {
+            (Some(kind), _, Err(_)) | (_, Some(kind), Ok(_)) => self.quotas.release(kind),
+            _ => {}
+        }
+        if declared.is_some() || undeclared.is_some() {
+            self.admin_client
+                .lock()
+                .unwrap()
+                .set_entity_usage(self.quotas.usage());
+        }
+        result
+    }
+
+    /// Undeclares an entity on behalf of the administrator, bypassing the access control
+    /// and the rate limits the client is subject to.
+    pub(crate) async fn revoke(
+        &mut self,
+        entity: RevokedEntity,
+    ) -> Result<(), zenoh_result::Error> {
+        tracing::trace!("revoke: {}", entity);
+        let msg = entity.undeclare();
+        let undeclared = EntityKind::undeclared_by(&msg);
+        self.execute(msg).await?;
+        if let Some(kind) = undeclared {
+            self.quotas.release(kind);
+            self.admin_client
+                .lock()
+                .unwrap()
+                .set_entity_usage(self.quotas.usage());
+        }
+        Ok(())
+    }
+
+    async fn execute(
+        &mut self,
+        msg: InRemoteMessage,
+    ) -> Result<Option<OutRemoteMessage>, zenoh_result::Error> {
         match msg {
             InRemoteMessage::DeclarePublisher(declare_publisher) => {
                 self.declare_publisher(declare_publisher).await
@@ -1205,12 +1691,20 @@ impl RemoteState {
             InRemoteMessage::UndeclarePublisher(undeclare_publisher) => {
                 self.undeclare_publisher(undeclare_publisher).await
             }
+            InRemoteMessage::DeclareAdvancedPublisher(declare_advanced_publisher) => {
+                self.declare_advanced_publisher(declare_advanced_publisher)
+                    .await
+            }
             InRemoteMessage::DeclareSubscriber(declare_subscriber) => {
                 self.declare_subscriber(declare_subscriber).await
             }
             InRemoteMessage::UndeclareSubscriber(undeclare_subscriber) => {
                 self.undeclare_subscriber(undeclare_subscriber).await
             }
+            InRemoteMessage::DeclareAdvancedSubscriber(declare_advanced_subscriber) => {
+                self.declare_advanced_subscriber(declare_advanced_subscriber)
+                    .await
+            }
             InRemoteMessage::DeclareQueryable(declare_queryable) => {
                 self.declare_queryable(declare_queryable).await
             }
@@ -1288,6 +1782,8 @@ impl RemoteState {
             InRemoteMessage::Ping(_) => Ok(Some(OutRemoteMessage::PingAck(PingAck {
                 uuid: self.id.clone(),
             }))),
+            // answered by the connection, which negotiates the version of the protocol
+            InRemoteMessage::Hello(_) => Ok(None),
             InRemoteMessage::PublisherDeclareMatchingListener(
                 publisher_declare_matching_listener,
             ) => {
@@ -1327,3 +1823,299 @@ impl RemoteState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use zenoh::{
+        bytes::Encoding,
+        key_expr::OwnedKeyExpr,
+        qos::{CongestionControl, Priority, Reliability},
+        sample::Locality,
+        session::EntityGlobalId,
+    };
+
+    use super::{forward_sample_miss, RemoteState};
+    use crate::{
+        acl::{Acl, ClientAcl},
+        commands::command_channel,
+        config::{
+            AccessControl, AccessControlAction, AccessControlRule, EntityLimits, Permission, Quotas,
+        },
+        interface::{
+            self, DeclareAdvancedPublisher, DeclareLivelinessSubscriber, DeclareLivelinessToken,
+            DeclarePublisher, DeclareQueryable, DeclareSubscriber, LivelinessGet,
+            LivelinessTokenId, MatchingListenerId, PublisherDeclareMatchingListener, PublisherId,
+            Qos, QueryId, QueryableId, SubscriberId, UndeclareLivelinessToken,
+            UndeclareMatchingListener, UndeclarePublisher, UndeclareQueryable, UndeclareSubscriber,
+        },
+        outbound::{outbound_channel, OutboundReceiver},
+        protocol::PROTOCOL_VERSION,
+        quotas::{ClientQuotas, GlobalQuotas},
+        ratelimit::RateLimits,
+        AdminSpaceClient, AdminSpaceEntity, InRemoteMessage, OutRemoteMessage,
+    };
+
+    fn ke(s: &str) -> OwnedKeyExpr {
+        OwnedKeyExpr::new(s).unwrap()
+    }
+
+    async fn remote_state(
+        acl: ClientAcl,
+        quotas: Option<&Quotas>,
+    ) -> (RemoteState, OutboundReceiver, Arc<Mutex<AdminSpaceClient>>) {
+        let mut config = zenoh::Config::default();
+        config
+            .insert_json5("scouting/multicast/enabled", "false")
+            .unwrap();
+        config.insert_json5("listen/endpoints", "[]").unwrap();
+        let session = zenoh::open(config).await.unwrap();
+        let admin_client = Arc::new(Mutex::new(AdminSpaceClient::new(
+            "a".to_string(),
+            "127.0.0.1:50000".to_string(),
+            "[::]:10000".to_string(),
+            None,
+            None,
+            command_channel().0,
+        )));
+        let (tx, rx) = outbound_channel(None, admin_client.clone());
+        let state = RemoteState::new(
+            tx,
+            admin_client.clone(),
+            session,
+            acl,
+            ClientQuotas::new(Arc::new(GlobalQuotas::new(quotas))),
+            RateLimits::new(&[]).unwrap().client_limiter(),
+        );
+        (state, rx, admin_client)
+    }
+
+    fn is_error(res: &Option<OutRemoteMessage>, prefix: &str) -> bool {
+        matches!(res, Some(OutRemoteMessage::Error(interface::Error { error }))
+            if error.starts_with(prefix))
+    }
+
+    fn is_denied(res: &Option<OutRemoteMessage>) -> bool {
+        is_error(res, "Access denied")
+    }
+
+    fn deny(actions: Vec<AccessControlAction>, key_expr: &str) -> ClientAcl {
+        Acl::new(&AccessControl {
+            default_permission: Permission::Allow,
+            rules: vec![AccessControlRule {
+                permission: Permission::Deny,
+                actions,
+                key_exprs: vec![key_expr.to_string()],
+                users: Vec::new(),
+                cert_subjects: Vec::new(),
+            }],
+        })
+        .unwrap()
+        .client_acl(None, None)
+    }
+
+    fn qos() -> Qos {
+        Qos::new(
+            Priority::Data,
+            CongestionControl::Drop,
+            false,
+            Reliability::Reliable,
+            Locality::Any,
+        )
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_liveliness_access() {
+        let acl = deny(
+            vec![AccessControlAction::Subscribe, AccessControlAction::Query],
+            "secret/**",
+        );
+        let (mut state, _rx, admin_client) = remote_state(acl, None).await;
+
+        let subscribe = |id, keyexpr| {
+            InRemoteMessage::DeclareLivelinessSubscriber(DeclareLivelinessSubscriber {
+                id: SubscriberId(id),
+                keyexpr: ke(keyexpr),
+                history: false,
+            })
+        };
+        let res = state
+            .handle_message(subscribe(1, "secret/a"))
+            .await
+            .unwrap();
+        assert!(is_denied(&res));
+        assert!(admin_client
+            .lock()
+            .unwrap()
+            .liveliness_subscribers
+            .is_empty());
+        let res = state.handle_message(subscribe(2, "**")).await.unwrap();
+        assert!(is_denied(&res));
+        let res = state
+            .handle_message(subscribe(3, "public/a"))
+            .await
+            .unwrap();
+        assert!(!is_denied(&res));
+        assert_eq!(admin_client.lock().unwrap().liveliness_subscribers.len(), 1);
+
+        let get = |id, keyexpr| {
+            InRemoteMessage::LivelinessGet(LivelinessGet {
+                id: QueryId(id),
+                keyexpr: ke(keyexpr),
+                timeout_ms: 100,
+            })
+        };
+        let res = state.handle_message(get(1, "secret/*")).await.unwrap();
+        assert!(is_denied(&res));
+        let res = state.handle_message(get(2, "public/*")).await.unwrap();
+        assert!(!is_denied(&res));
+        state.clear().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_advanced_publisher_policies() {
+        let acl = deny(vec![AccessControlAction::Publish], "secret/**");
+        let quotas = Quotas {
+            per_client: EntityLimits {
+                publishers: Some(1),
+                ..Default::default()
+            },
+            global: Default::default(),
+        };
+        let (mut state, _rx, admin_client) = remote_state(acl, Some(&quotas)).await;
+        let declare = |id, keyexpr| {
+            InRemoteMessage::DeclareAdvancedPublisher(DeclareAdvancedPublisher {
+                id: PublisherId(id),
+                keyexpr: ke(keyexpr),
+                encoding: Encoding::default(),
+                qos: qos(),
+                cache_max_samples: Some(10),
+                sample_miss_detection: None,
+                publisher_detection: false,
+                publisher_detection_metadata: None,
+            })
+        };
+        let publishers = || admin_client.lock().unwrap().publishers.len();
+
+        // not before the negotiation of the version introducing it
+        let res = state.handle_message(declare(1, "public/a")).await.unwrap();
+        assert!(is_error(&res, "The message requires protocol version"));
+        state.set_protocol_version(PROTOCOL_VERSION);
+
+        let res = state.handle_message(declare(1, "secret/a")).await.unwrap();
+        assert!(is_denied(&res));
+        assert_eq!(publishers(), 0);
+
+        let res = state.handle_message(declare(1, "public/a")).await.unwrap();
+        assert!(res.is_none());
+        assert_eq!(publishers(), 1);
+        let res = state.handle_message(declare(2, "public/b")).await.unwrap();
+        assert!(is_error(&res, "Quota exceeded"));
+        assert_eq!(publishers(), 1);
+
+        let undeclare =
+            InRemoteMessage::UndeclarePublisher(UndeclarePublisher { id: PublisherId(1) });
+        assert!(state.handle_message(undeclare).await.unwrap().is_none());
+        assert_eq!(publishers(), 0);
+        let res = state.handle_message(declare(2, "public/b")).await.unwrap();
+        assert!(res.is_none());
+        assert_eq!(publishers(), 1);
+        state.clear().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_admin_space_entities() {
+        let (mut state, _rx, admin_client) = remote_state(ClientAcl::allow_all(), None).await;
+        let messages = [
+            InRemoteMessage::DeclarePublisher(DeclarePublisher {
+                id: PublisherId(1),
+                keyexpr: ke("demo/pub"),
+                encoding: Encoding::default(),
+                qos: qos(),
+            }),
+            InRemoteMessage::PublisherDeclareMatchingListener(PublisherDeclareMatchingListener {
+                id: MatchingListenerId(1),
+                publisher_id: PublisherId(1),
+            }),
+            InRemoteMessage::DeclareSubscriber(DeclareSubscriber {
+                id: SubscriberId(1),
+                keyexpr: ke("demo/sub"),
+                allowed_origin: Locality::Any,
+            }),
+            InRemoteMessage::DeclareQueryable(DeclareQueryable {
+                id: QueryableId(1),
+                keyexpr: ke("demo/queryable"),
+                complete: true,
+                allowed_origin: Locality::Any,
+            }),
+            InRemoteMessage::DeclareLivelinessToken(DeclareLivelinessToken {
+                id: LivelinessTokenId(1),
+                keyexpr: ke("demo/token"),
+            }),
+        ];
+        for msg in messages {
+            assert!(state.handle_message(msg).await.unwrap().is_none());
+        }
+        {
+            let admin_client = admin_client.lock().unwrap();
+            let key_expr = |entity: Option<&AdminSpaceEntity>| {
+                entity.and_then(|entity| entity.key_expr.clone())
+            };
+            let publisher = admin_client.publishers.get(&PublisherId(1));
+            assert_eq!(key_expr(publisher).as_deref(), Some("demo/pub"));
+            let listener = admin_client.matching_listeners.get(&MatchingListenerId(1));
+            assert_eq!(key_expr(listener).as_deref(), Some("demo/pub"));
+            let subscriber = admin_client.subscribers.get(&SubscriberId(1));
+            assert_eq!(key_expr(subscriber).as_deref(), Some("demo/sub"));
+            let queryable = admin_client.queryables.get(&QueryableId(1));
+            assert_eq!(key_expr(queryable).as_deref(), Some("demo/queryable"));
+            let token = admin_client.liveliness_tokens.get(&LivelinessTokenId(1));
+            assert_eq!(key_expr(token).as_deref(), Some("demo/token"));
+        }
+
+        let messages = [
+            InRemoteMessage::UndeclareMatchingListener(UndeclareMatchingListener {
+                id: MatchingListenerId(1),
+            }),
+            InRemoteMessage::UndeclarePublisher(UndeclarePublisher { id: PublisherId(1) }),
+            InRemoteMessage::UndeclareSubscriber(UndeclareSubscriber {
+                id: SubscriberId(1),
+            }),
+            InRemoteMessage::UndeclareQueryable(UndeclareQueryable { id: QueryableId(1) }),
+            InRemoteMessage::UndeclareLivelinessToken(UndeclareLivelinessToken {
+                id: LivelinessTokenId(1),
+            }),
+        ];
+        for msg in messages {
+            assert!(state.handle_message(msg).await.unwrap().is_none());
+        }
+        {
+            let admin_client = admin_client.lock().unwrap();
+            assert!(admin_client.publishers.is_empty());
+            assert!(admin_client.matching_listeners.is_empty());
+            assert!(admin_client.subscribers.is_empty());
+            assert!(admin_client.queryables.is_empty());
+            assert!(admin_client.liveliness_tokens.is_empty());
+        }
+        state.clear().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_forward_sample_miss() {
+        let (mut state, rx, _admin_client) = remote_state(ClientAcl::allow_all(), None).await;
+        let source = EntityGlobalId::new(state.session.zid(), 5);
+        forward_sample_miss(&state.tx, SubscriberId(3), source, 2);
+        match rx.try_recv() {
+            Some((OutRemoteMessage::SampleMiss(miss), None)) => {
+                assert_eq!(miss.subscriber_id, SubscriberId(3));
+                assert_eq!(miss.source_zid, source.zid());
+                assert_eq!(miss.source_eid, 5);
+                assert_eq!(miss.nb, 2);
+            }
+            _ => panic!("the miss is forwarded to the client"),
+        }
+        assert!(rx.try_recv().is_none());
+        state.clear().await;
+    }
+}
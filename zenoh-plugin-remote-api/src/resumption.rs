@@ -0,0 +1,249 @@
+//
+// Copyright (c) 2026 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use tokio_tungstenite::tungstenite::handshake::server::Request;
+use uuid::Uuid;
+
+use crate::{
+    commands::CommandReceiver,
+    connections::ClientSlot,
+    outbound::{OutboundReceiver, OutboundSender},
+    remote_state::RemoteState,
+    AdminSpaceClient,
+};
+
+/// The query parameter of the upgrade request by which a client opts in to session resumption:
+/// empty to be issued a resume token, or set to a token previously issued to resume its session.
+const RESUME_QUERY_PARAMETER: &str = "resume";
+
+/// Returns the value of the resume query parameter of the upgrade request, if present.
+pub(crate) fn requested_resumption(request: &Request) -> Option<String> {
+    let query = request.uri().query()?;
+    form_urlencoded::parse(query.as_bytes())
+        .find(|(name, _)| name == RESUME_QUERY_PARAMETER)
+        .map(|(_, value)| value.into_owned())
+}
+
+pub(crate) fn new_resume_token() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
+/// The state of a client, which outlives its WebSocket connection if it opted in to resumption.
+pub(crate) struct ClientSession {
+    pub(crate) id: Uuid,
+    pub(crate) user: Option<String>,
+    pub(crate) admin_client: Arc<Mutex<AdminSpaceClient>>,
+    pub(crate) remote_state: RemoteState,
+    pub(crate) tx: OutboundSender,
+    /// Keeps the outbound queue open while the client is disconnected,
+    /// so that the messages for it are buffered until it reconnects.
+    /// Shared with the task sending the messages of the current connection.
+    pub(crate) rx: Arc<OutboundReceiver>,
+    pub(crate) commands: CommandReceiver,
+    /// Counts the client in the maximum number of clients until its session ends.
+    pub(crate) _slot: ClientSlot,
+}
+
+/// The sessions of the disconnected clients, by resume token, during their grace period.
+#[derive(Default)]
+pub(crate) struct SuspendedSessions {
+    sessions: Mutex<HashMap<String, ClientSession>>,
+}
+
+impl SuspendedSessions {
+    /// Keeps the session of a disconnected client. Its outbound queue buffers the messages
+    /// for it up to its capacity, the session ends if it overflows.
+    pub(crate) fn suspend(&self, token: String, session: ClientSession) {
+        session.rx.suspend();
+        self.sessions.lock().unwrap().insert(token, session);
+    }
+
+    /// Takes the session of a resume token, unless it belongs to another authenticated user
+    /// or its outbound queue overflowed.
+    pub(crate) fn resume(&self, token: &str, user: Option<&str>) -> Option<ClientSession> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions.get(token)?;
+        if session.user.as_deref() != user {
+            tracing::warn!("Rejected the resumption of a session by another user");
+            return None;
+        }
+        if session.tx.is_closed() {
+            return None;
+        }
+        let session = sessions.remove(token)?;
+        session.rx.resume();
+        Some(session)
+    }
+
+    /// Takes the session of a resume token whose grace period ended,
+    /// `None` if it was resumed in the meantime.
+    pub(crate) fn expire(&self, token: &str) -> Option<ClientSession> {
+        self.sessions.lock().unwrap().remove(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tokio_tungstenite::tungstenite::handshake::server::Request;
+    use uuid::Uuid;
+
+    use super::{requested_resumption, ClientSession, SuspendedSessions};
+    use crate::{
+        acl::ClientAcl,
+        commands::command_channel,
+        config::{OutboundQueue, OverflowPolicy},
+        connections::ClientCount,
+        interface::{self, InRemoteMessageId, OutRemoteMessage},
+        outbound::{outbound_channel, OutboundMessage},
+        quotas::{ClientQuotas, GlobalQuotas},
+        ratelimit::RateLimits,
+        remote_state::RemoteState,
+        AdminSpaceClient,
+    };
+
+    fn request(uri: &str) -> Request {
+        Request::builder().uri(uri).body(()).unwrap()
+    }
+
+    fn ok(seq: u32) -> OutboundMessage {
+        let msg = OutRemoteMessage::Ok(interface::Ok {
+            content_id: InRemoteMessageId::Ping,
+        });
+        (msg, Some(seq))
+    }
+
+    async fn client_session(user: &str, capacity: usize) -> ClientSession {
+        let mut config = zenoh::Config::default();
+        config
+            .insert_json5("scouting/multicast/enabled", "false")
+            .unwrap();
+        config.insert_json5("listen/endpoints", "[]").unwrap();
+        let session = zenoh::open(config).await.unwrap();
+        let id = Uuid::new_v4();
+        let (commands_tx, commands) = command_channel();
+        let admin_client = Arc::new(Mutex::new(AdminSpaceClient::new(
+            id.to_string(),
+            "127.0.0.1:50000".to_string(),
+            "[::]:10000".to_string(),
+            Some(user.to_string()),
+            None,
+            commands_tx,
+        )));
+        let (tx, rx) = outbound_channel(
+            Some(&OutboundQueue {
+                capacity,
+                overflow_policy: OverflowPolicy::Block,
+            }),
+            admin_client.clone(),
+        );
+        let remote_state = RemoteState::new(
+            tx.clone(),
+            admin_client.clone(),
+            session,
+            ClientAcl::allow_all(),
+            ClientQuotas::new(Arc::new(GlobalQuotas::new(None))),
+            RateLimits::new(&[]).unwrap().client_limiter(),
+        );
+        ClientSession {
+            id,
+            user: Some(user.to_string()),
+            admin_client,
+            remote_state,
+            tx,
+            rx: Arc::new(rx),
+            commands,
+            _slot: ClientCount::default().acquire(None).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_requested_resumption() {
+        assert_eq!(requested_resumption(&request("ws://localhost:10000")), None);
+        assert_eq!(
+            requested_resumption(&request("ws://localhost:10000/?token=abc")),
+            None
+        );
+        assert_eq!(
+            requested_resumption(&request("ws://localhost:10000/?resume")).as_deref(),
+            Some("")
+        );
+        assert_eq!(
+            requested_resumption(&request("ws://localhost:10000/?token=abc&resume=1a2b"))
+                .as_deref(),
+            Some("1a2b")
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_resume() {
+        let suspended = SuspendedSessions::default();
+        let session = client_session("alice", 2).await;
+        let (id, tx) = (session.id, session.tx.clone());
+        suspended.suspend("token".to_string(), session);
+
+        // the messages for the client are buffered while it is disconnected
+        tx.send(ok(1)).unwrap();
+        tx.send(ok(2)).unwrap();
+
+        assert!(suspended.resume("token", Some("bob")).is_none());
+        assert!(suspended.resume("token", None).is_none());
+        assert!(suspended.resume("other", Some("alice")).is_none());
+        let session = suspended.resume("token", Some("alice")).unwrap();
+        assert_eq!(session.id, id);
+        assert_eq!(session.rx.try_recv().unwrap().1, Some(1));
+        assert_eq!(session.rx.try_recv().unwrap().1, Some(2));
+        assert!(session.rx.try_recv().is_none());
+        // resumed before the end of its grace period
+        assert!(suspended.expire("token").is_none());
+
+        // the resumed queue waits for room again
+        tx.send(ok(3)).unwrap();
+        tx.send(ok(4)).unwrap();
+        let sender = tokio::spawn({
+            let tx = tx.clone();
+            async move { tx.send(ok(5)) }
+        });
+        assert_eq!(session.rx.recv().await.1, Some(3));
+        sender.await.unwrap().unwrap();
+        assert!(!tx.is_closed());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_overflow_while_suspended() {
+        let suspended = SuspendedSessions::default();
+        let session = client_session("alice", 1).await;
+        let tx = session.tx.clone();
+        tx.send(ok(1)).unwrap();
+        // a callback waiting for room is released when the session is suspended
+        let sender = tokio::spawn({
+            let tx = tx.clone();
+            async move { tx.send(ok(2)) }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        suspended.suspend("token".to_string(), session);
+        assert!(sender.await.unwrap().is_err());
+        assert!(tx.is_closed());
+
+        // the client missed messages, its session can't be resumed
+        assert!(suspended.resume("token", Some("alice")).is_none());
+        assert!(suspended.expire("token").is_some());
+    }
+}
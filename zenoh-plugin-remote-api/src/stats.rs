@@ -0,0 +1,149 @@
+//
+// Copyright (c) 2026 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    collections::BTreeMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+/// The current time, in milliseconds since the UNIX epoch.
+pub(crate) fn unix_time_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+/// The number of messages of a kind, and their size.
+#[derive(Debug, Default, Serialize, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Traffic {
+    pub(crate) messages: u64,
+    pub(crate) bytes: u64,
+}
+
+impl Traffic {
+    fn add(&mut self, bytes: usize) {
+        self.messages += 1;
+        self.bytes += bytes as u64;
+    }
+
+    fn merge(&mut self, other: &Traffic) {
+        self.messages += other.messages;
+        self.bytes += other.bytes;
+    }
+}
+
+/// The traffic of a client, as shown in the admin space. The times are in milliseconds
+/// since the UNIX epoch.
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct ClientStats {
+    /// When the client connected, or reconnected to resume its session.
+    connected_at: u64,
+    /// When a message was last received from or sent to the client.
+    last_activity: u64,
+    /// The messages received from the client by kind, as they were serialized.
+    received: BTreeMap<&'static str, Traffic>,
+    /// The messages sent to the client by kind, before their compression.
+    sent: BTreeMap<&'static str, Traffic>,
+    /// The messages received from the client which couldn't be parsed or executed.
+    errors: u64,
+}
+
+impl ClientStats {
+    pub(crate) fn new() -> Self {
+        let now = unix_time_millis();
+        ClientStats {
+            connected_at: now,
+            last_activity: now,
+            received: BTreeMap::new(),
+            sent: BTreeMap::new(),
+            errors: 0,
+        }
+    }
+
+    pub(crate) fn connected(&mut self) {
+        self.connected_at = unix_time_millis();
+        self.last_activity = self.connected_at;
+    }
+
+    pub(crate) fn received(&mut self, kind: &'static str, bytes: usize) {
+        self.received.entry(kind).or_default().add(bytes);
+        self.last_activity = unix_time_millis();
+    }
+
+    pub(crate) fn sent(&mut self, kind: &'static str, bytes: usize) {
+        self.sent.entry(kind).or_default().add(bytes);
+        self.last_activity = unix_time_millis();
+    }
+
+    pub(crate) fn error(&mut self) {
+        self.errors += 1;
+    }
+
+    /// Adds the traffic of another client, e.g. to total the traffic of the clients of a listener.
+    pub(crate) fn merge(&mut self, other: &ClientStats) {
+        for (kind, traffic) in &other.received {
+            self.received.entry(kind).or_default().merge(traffic);
+        }
+        for (kind, traffic) in &other.sent {
+            self.sent.entry(kind).or_default().merge(traffic);
+        }
+        self.errors += other.errors;
+        self.last_activity = self.last_activity.max(other.last_activity);
+    }
+
+    pub(crate) fn received_traffic(&self) -> &BTreeMap<&'static str, Traffic> {
+        &self.received
+    }
+
+    pub(crate) fn sent_traffic(&self) -> &BTreeMap<&'static str, Traffic> {
+        &self.sent
+    }
+
+    pub(crate) fn errors(&self) -> u64 {
+        self.errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClientStats, Traffic};
+
+    #[test]
+    fn test_client_stats() {
+        let mut stats = ClientStats::new();
+        stats.received("Put", 10);
+        stats.received("Put", 20);
+        stats.received("Get", 5);
+        stats.sent("Sample", 100);
+        stats.error();
+
+        assert_eq!(
+            stats.received["Put"],
+            Traffic {
+                messages: 2,
+                bytes: 30
+            }
+        );
+        assert_eq!(stats.received["Get"].messages, 1);
+        assert_eq!(stats.sent["Sample"].bytes, 100);
+        assert_eq!(stats.errors, 1);
+        assert!(stats.last_activity >= stats.connected_at);
+
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["received"]["Put"]["messages"], 2);
+        assert_eq!(json["errors"], 1);
+    }
+}
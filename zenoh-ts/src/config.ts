@@ -30,7 +30,10 @@ export class Config {
    * - common url form, e.g. `ws://127.0.0.1:10000`
    * @param {number} messageResponseTimeoutMs - timeout value in milliseconds for receiving a response from zenoh-plugin-remote-api.
    * Defaults to 500 ms.
+   * @param {boolean} resume - whether to resume the session when the websocket is disconnected, keeping its declarations
+   * on the remote-api plugin for the grace period configured by its `session_resumption` setting.
+   * Defaults to false.
    * @returns {Config} configuration instance
    */
-  constructor(public locator: string, public messageResponseTimeoutMs: number = 500) {}
+  constructor(public locator: string, public messageResponseTimeoutMs: number = 500, public resume: boolean = false) {}
 }
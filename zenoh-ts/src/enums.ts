@@ -103,4 +103,14 @@ export enum ReplyKeyExpr {
     // // Accept replies whose key expressions match the query key expression.
     MATCHING_QUERY = 1,
     DEFAULT = MATCHING_QUERY
-}
\ No newline at end of file
+}
+
+// The heartbeat by which an advanced publisher lets the advanced subscribers detect the samples they missed.
+export enum HeartbeatMode {
+    // Misses are only detected on the reception of the next sample.
+    NONE = 0,
+    // Sent periodically.
+    PERIODIC = 1,
+    // Sent periodically, only if samples were published since the last one.
+    SPORADIC = 2,
+}
@@ -25,8 +25,9 @@ export {
   SampleKind,
   ReplyKeyExpr,
   WhatAmI,
+  HeartbeatMode,
 } from "./enums.js";
-export { Sample } from "./sample.js";
+export { Sample, SampleMiss } from "./sample.js";
 export { Timestamp } from "./timestamp.js";
 export { ZenohId } from "./zid.js";
 export { Publisher, Subscriber, PublisherPutOptions, PublisherDeleteOptions } from "./pubsub.js";
@@ -51,6 +52,11 @@ export {
   QueryableOptions,
   QuerierOptions,
   PublisherOptions,
+  AdvancedPublisherOptions,
+  SampleMissDetectionOptions,
+  AdvancedSubscriberOptions,
+  HistoryOptions,
+  RecoveryOptions,
   SessionInfo,
   SubscriberOptions,
   open
@@ -66,6 +66,13 @@ export class RemoteLink {
     throw new Error(`Failed to connect to locator endpoint: ${locator} after ${MAX_RETRIES}`);
   }
 
+  onclose(onclose: () => void) {
+    this.ws.onclose = function (event: any) {
+      console.warn(`WebSocket has been disconnected from remote-api-plugin: ${event.code}`)
+      onclose();
+    };
+  }
+
   onmessage(onmessage: (msg: Uint8Array) => void) {
     this.ws.onmessage = function (event: any) {
       onmessage(new Uint8Array(event.data));
@@ -15,10 +15,10 @@
 import { ZBytesDeserializer, ZBytesSerializer, ZD } from "./ext/index.js"
 import { Encoding } from "./encoding.js";
 import { KeyExpr } from "./key_expr.js";
-import { Locality, Reliability, CongestionControl, Priority, SampleKind, ConsolidationMode, ReplyKeyExpr, QueryTarget, WhatAmI } from "./enums.js";
+import { Locality, Reliability, CongestionControl, Priority, SampleKind, ConsolidationMode, ReplyKeyExpr, QueryTarget, WhatAmI, HeartbeatMode } from "./enums.js";
 import { Timestamp } from "./timestamp.js";
 import { ZenohId } from "./zid.js";
-import { Sample } from "./sample.js";
+import { Sample, SampleMiss } from "./sample.js";
 import { Parameters, QueryInner, Reply, ReplyError } from "./query.js";
 import { ZBytes } from "./z_bytes.js";
 import { PublisherId, SubscriberId, QueryableId, QuerierId, LivelinessTokenId, GetId, MatchingListenerId, TransportEventsListenerId, LinkEventsListenerId } from "./session_inner.js";
@@ -136,6 +136,24 @@ function serializeOptEncoding(e: Encoding | undefined, serializer: ZBytesSeriali
     }
 }
 
+function serializeOptNumberUint32(val: number | undefined, serializer: ZBytesSerializer) {
+    if (val == undefined) {
+        serializer.serializeBoolean(false);
+    } else {
+        serializer.serializeBoolean(true);
+        serializer.serializeNumberUint32(val);
+    }
+}
+
+function serializeOptNumberUint64(val: number | undefined, serializer: ZBytesSerializer) {
+    if (val == undefined) {
+        serializer.serializeBoolean(false);
+    } else {
+        serializer.serializeBoolean(true);
+        serializer.serializeNumberUint64(val);
+    }
+}
+
 function deserializeEncoding(deserializer: ZBytesDeserializer): Encoding {
     let id = deserializer.deserializeNumberUint16();
     let schema: string | undefined = deserializer.deserializeString();
@@ -295,6 +313,38 @@ export enum OutRemoteMessageId {
     UndeclareTransportEventsListener,
     DeclareLinkEventsListener,
     UndeclareLinkEventsListener,
+    Hello,
+    DeclareAdvancedPublisher,
+    DeclareAdvancedSubscriber,
+}
+
+// The latest version of the protocol spoken with the remote api plugin.
+export const PROTOCOL_VERSION = 3;
+// The version of the protocol spoken by the plugins which don't answer the Hello message.
+export const LEGACY_PROTOCOL_VERSION = 1;
+
+// The version of the protocol which introduced the advanced publishers and subscribers.
+export const ADVANCED_PUB_SUB_PROTOCOL_VERSION = 3;
+
+// The optional features of the protocol, as bits of the Hello and HelloAck messages.
+export const FEATURE_RESUMPTION = 1 << 0;
+export const FEATURE_COMPRESSION = 1 << 1;
+export const FEATURE_BATCHING = 1 << 2;
+
+// Sent right after connecting, with the range of versions of the protocol and the features supported by the session.
+export class Hello {
+    public readonly outMessageId: OutRemoteMessageId = OutRemoteMessageId.Hello;
+    public constructor(
+        public readonly version: number,
+        public readonly minVersion: number,
+        public readonly features: number,
+    ) {}
+
+    public serializeWithZSerializer(serializer: ZBytesSerializer) {
+        serializer.serializeNumberUint32(this.version);
+        serializer.serializeNumberUint32(this.minVersion);
+        serializer.serializeNumberUint32(this.features);
+    }
 }
 
 export type PublisherProperties = {
@@ -318,6 +368,48 @@ export class DeclarePublisher {
     }
 }
 
+export type SampleMissDetection = {
+    heartbeat: HeartbeatMode,
+    periodMs: number,
+};
+
+export type AdvancedPublisherProperties = PublisherProperties & {
+    cacheMaxSamples?: number,
+    sampleMissDetection?: SampleMissDetection,
+    publisherDetection: boolean,
+    publisherDetectionMetadata?: KeyExpr,
+};
+
+export class DeclareAdvancedPublisher {
+    public readonly outMessageId: OutRemoteMessageId = OutRemoteMessageId.DeclareAdvancedPublisher;
+    public constructor(
+        public readonly id: PublisherId,
+        public readonly properties: AdvancedPublisherProperties,
+    ) {}
+
+    public serializeWithZSerializer(serializer: ZBytesSerializer) {
+        serializer.serializeNumberUint32(this.id);
+        serializer.serializeString(this.properties.keyexpr.toString());
+        serializeEncoding(this.properties.encoding, serializer);
+        serializer.serializeNumberUint8(qosToUint8(this.properties.qos));
+        serializeOptNumberUint32(this.properties.cacheMaxSamples, serializer);
+        if (this.properties.sampleMissDetection == undefined) {
+            serializer.serializeBoolean(false);
+        } else {
+            serializer.serializeBoolean(true);
+            serializer.serializeNumberUint8(this.properties.sampleMissDetection.heartbeat);
+            serializer.serializeNumberUint64(this.properties.sampleMissDetection.periodMs);
+        }
+        serializer.serializeBoolean(this.properties.publisherDetection);
+        if (this.properties.publisherDetectionMetadata == undefined) {
+            serializer.serializeBoolean(false);
+        } else {
+            serializer.serializeBoolean(true);
+            serializer.serializeString(this.properties.publisherDetectionMetadata.toString());
+        }
+    }
+}
+
 export class UndeclarePublisher {
     public readonly outMessageId: OutRemoteMessageId = OutRemoteMessageId.UndeclarePublisher;
     public constructor(
@@ -348,6 +440,57 @@ export class DeclareSubscriber {
     }
 }
 
+export type History = {
+    detectLatePublishers: boolean,
+    maxSamples?: number,
+    maxAgeMs?: number,
+};
+
+// How an advanced subscriber retrieves the samples it missed:
+// when notified by the heartbeat of the publishers if there is no period, by querying them periodically otherwise.
+export type Recovery = {
+    periodicQueriesMs?: number,
+};
+
+export type AdvancedSubscriberProperties = SubscriberProperties & {
+    history?: History,
+    recovery?: Recovery,
+    queryTimeoutMs?: number,
+    subscriberDetection: boolean,
+};
+
+export class DeclareAdvancedSubscriber {
+    public readonly outMessageId: OutRemoteMessageId = OutRemoteMessageId.DeclareAdvancedSubscriber;
+    public constructor(
+        public readonly id: SubscriberId,
+        public readonly properties: AdvancedSubscriberProperties
+    ) {}
+
+    public serializeWithZSerializer(serializer: ZBytesSerializer) {
+        serializer.serializeNumberUint32(this.id);
+        serializer.serializeString(this.properties.keyexpr.toString());
+        serializer.serializeNumberUint8(this.properties.allowedOrigin);
+        if (this.properties.history == undefined) {
+            serializer.serializeBoolean(false);
+        } else {
+            serializer.serializeBoolean(true);
+            serializer.serializeBoolean(this.properties.history.detectLatePublishers);
+            serializeOptNumberUint32(this.properties.history.maxSamples, serializer);
+            serializeOptNumberUint64(this.properties.history.maxAgeMs, serializer);
+        }
+        if (this.properties.recovery == undefined) {
+            serializer.serializeBoolean(false);
+        } else {
+            serializer.serializeBoolean(true);
+            const periodMs = this.properties.recovery.periodicQueriesMs;
+            serializer.serializeNumberUint8(periodMs == undefined ? 0 : 1);
+            serializer.serializeNumberUint64(periodMs ?? 0);
+        }
+        serializeOptNumberUint64(this.properties.queryTimeoutMs, serializer);
+        serializer.serializeBoolean(this.properties.subscriberDetection);
+    }
+}
+
 export class UndeclareSubscriber {
     public readonly outMessageId: OutRemoteMessageId = OutRemoteMessageId.UndeclareSubscriber;
     public constructor(
@@ -859,6 +1002,10 @@ export enum InRemoteMessageId {
     ResponseLinks,
     InTransportEventUpdate,
     InLinkEventUpdate,
+    ResumeToken,
+    Batch,
+    HelloAck,
+    InSampleMiss,
 }
 
 export class ResponsePing {
@@ -1111,18 +1258,98 @@ export class InLinkEventUpdate {
     }
 }
 
+export class ResumeToken {
+    public readonly inMessageId: InRemoteMessageId = InRemoteMessageId.ResumeToken;
+
+    public constructor(
+        public readonly token: string,
+        public readonly resumed: boolean,
+    ) {}
+
+    static deserialize(deserializer: ZBytesDeserializer): ResumeToken {
+        let token = deserializer.deserializeString();
+        let resumed = deserializer.deserializeBoolean();
+        return new ResumeToken(token, resumed);
+    }
+}
+
+export class HelloAck {
+    public readonly inMessageId: InRemoteMessageId = InRemoteMessageId.HelloAck;
+
+    public constructor(
+        public readonly version: number,
+        public readonly features: number,
+    ) {}
+
+    static deserialize(deserializer: ZBytesDeserializer): HelloAck {
+        let version = deserializer.deserializeNumberUint32();
+        let features = deserializer.deserializeNumberUint32();
+        return new HelloAck(version, features);
+    }
+}
+
+export class InSampleMiss {
+    public readonly inMessageId: InRemoteMessageId = InRemoteMessageId.InSampleMiss;
+
+    public constructor(
+        public readonly subscriberId: SubscriberId,
+        public readonly miss: SampleMiss,
+    ) {}
+
+    static deserialize(deserializer: ZBytesDeserializer): InSampleMiss {
+        let subscriberId = deserializer.deserializeNumberUint32() as SubscriberId;
+        let sourceZid = deserializeZenohId(deserializer);
+        let sourceEid = deserializer.deserializeNumberUint32();
+        let nb = deserializer.deserializeNumberUint32();
+        return new InSampleMiss(subscriberId, new SampleMiss(sourceZid, sourceEid, nb));
+    }
+}
+
+// Messages sent by the remote api plugin in a single websocket frame, each one with its own header.
+export class Batch {
+    public readonly inMessageId: InRemoteMessageId = InRemoteMessageId.Batch;
+
+    public constructor(
+        public readonly messages: Uint8Array[],
+    ) {}
+
+    static deserialize(deserializer: ZBytesDeserializer): Batch {
+        let messages = deserializer.deserializeArray(ZD.uint8array());
+        return new Batch(messages);
+    }
+}
+
 export interface OutMessageInterface {
     readonly outMessageId: OutRemoteMessageId;
     serializeWithZSerializer(serializer: ZBytesSerializer): void;
 }
 
 const ID_PRESENCE_FLAG = 0b10000000;
-const MESSAGE_ID_MASK = 0b01111111;
+// set by the remote api plugin on the messages whose body is compressed with raw deflate (RFC 1951)
+const COMPRESSED_FLAG = 0b01000000;
+const MESSAGE_ID_MASK = 0b00111111;
+
+// Decompresses the body of a message received from the remote api plugin, if it is compressed.
+// The header and the sequence id are not compressed.
+export async function decompressMessage(msg: Uint8Array): Promise<Uint8Array> {
+    const header = msg[0];
+    if (header == undefined || (header & COMPRESSED_FLAG) == 0) {
+        return msg;
+    }
+    const headerLength = (header & ID_PRESENCE_FLAG) == 0 ? 1 : 5;
+    const stream = new Blob([msg.slice(headerLength)]).stream().pipeThrough(new DecompressionStream("deflate-raw"));
+    const body = new Uint8Array(await new Response(stream).arrayBuffer());
+    const res = new Uint8Array(headerLength + body.length);
+    res.set(msg.subarray(0, headerLength));
+    res[0] = header & ~COMPRESSED_FLAG;
+    res.set(body, headerLength);
+    return res;
+}
 
 export function deserializeHeader(deserializer: ZBytesDeserializer): [InRemoteMessageId, number?] {
     let messageId = deserializer.deserializeNumberUint8();
     if ((messageId & ID_PRESENCE_FLAG) == 0) {
-        return [messageId, undefined];
+        return [messageId & MESSAGE_ID_MASK, undefined];
     } else {
         messageId = messageId & MESSAGE_ID_MASK;
         const sequenceId = deserializer.deserializeNumberUint32();
@@ -17,6 +17,7 @@ import { ZBytes } from "./z_bytes.js";
 import { Encoding } from "./encoding.js";
 import { CongestionControl, Priority, SampleKind } from "./enums.js";
 import { Timestamp } from "./timestamp.js";
+import { ZenohId } from "./zid.js";
 
 export class Sample {
     /**
@@ -62,3 +63,36 @@ export class Sample {
         return this.attachment_;
     }
 }
+
+/**
+ * Samples of a publisher which an advanced subscriber missed and could not recover.
+ */
+export class SampleMiss {
+    /**
+     * @internal
+     */
+    constructor(
+        private readonly sourceZid_: ZenohId,
+        private readonly sourceEid_: number,
+        private readonly nb_: number,
+    ) { }
+
+    /**
+     * returns the id of the session of the publisher
+     */
+    sourceZid(): ZenohId {
+        return this.sourceZid_;
+    }
+    /**
+     * returns the id of the publisher in its session
+     */
+    sourceEid(): number {
+        return this.sourceEid_;
+    }
+    /**
+     * returns the number of missed samples
+     */
+    nb(): number {
+        return this.nb_;
+    }
+}
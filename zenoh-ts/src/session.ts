@@ -30,10 +30,10 @@ import { Timestamp } from "./timestamp.js";
 import { ChannelReceiver, FifoChannel, Handler, intoCbDropReceiver } from "./channels.js";
 import { TransportInfo, LinkInfo, TransportEvent, LinkEvent, TransportEventsListener, LinkEventsListener, TransportEventsListenerOptions, LinkEventsListenerOptions } from "./connectivity.js";
 import { ZenohId } from "./zid.js";
-import { CongestionControl, ConsolidationMode, Locality, Priority, QueryTarget, Reliability, ReplyKeyExpr } from "./enums.js";
-import { Sample } from "./sample.js";
+import { CongestionControl, ConsolidationMode, HeartbeatMode, Locality, Priority, QueryTarget, Reliability, ReplyKeyExpr } from "./enums.js";
+import { Sample, SampleMiss } from "./sample.js";
 import { SessionInner, SubscriberKind } from "./session_inner.js";
-import { Delete, Put, Qos, QuerierProperties, QuerySettings } from "./message.js";
+import { AdvancedPublisherProperties, AdvancedSubscriberProperties, Delete, PublisherProperties, Put, Qos, QuerierProperties, QuerySettings } from "./message.js";
 import { Querier } from "./querier.js";
 import { CancellationToken } from "./cancellation_token.js";
 
@@ -143,6 +143,30 @@ export interface PublisherOptions {
     allowedDestination?: Locality
 }
 
+/**
+ * Options for the detection of the samples missed by the advanced subscribers
+ * @prop {HeartbeatMode=} heartbeat - Heartbeat sent by the publisher, misses are only detected on the reception of the next sample if none
+ * @prop {TimeDuration=} period - Period of the heartbeat
+ */
+export interface SampleMissDetectionOptions {
+    heartbeat?: HeartbeatMode,
+    period?: TimeDuration,
+}
+
+/**
+ * Options for an advanced Publisher
+ * @prop {number=} cacheMaxSamples - Number of samples kept per key for the late joiners and the recovery of the missed samples, none if not set
+ * @prop {SampleMissDetectionOptions=} sampleMissDetection - Lets the advanced subscribers detect the samples they missed, if set
+ * @prop {boolean=} publisherDetection - Lets the advanced subscribers detect this publisher
+ * @prop {IntoKeyExpr=} publisherDetectionMetadata - Metadata of the publisher detection
+ */
+export interface AdvancedPublisherOptions extends PublisherOptions {
+    cacheMaxSamples?: number,
+    sampleMissDetection?: SampleMissDetectionOptions,
+    publisherDetection?: boolean,
+    publisherDetectionMetadata?: IntoKeyExpr,
+}
+
 /**
  * Options for a Subscriber
  * @prop {Locality=} allowedOrigin - Origin of messages this subscriber can receive
@@ -153,6 +177,42 @@ export interface SubscriberOptions {
     handler?: Handler<Sample>,
 }
 
+/**
+ * Options for the history retrieved by an advanced Subscriber
+ * @prop {boolean=} detectLatePublishers - Also retrieves the history of the publishers appearing later
+ * @prop {number=} maxSamples - Maximum number of samples retrieved per key
+ * @prop {TimeDuration=} maxAge - Maximum age of the samples retrieved
+ */
+export interface HistoryOptions {
+    detectLatePublishers?: boolean,
+    maxSamples?: number,
+    maxAge?: TimeDuration,
+}
+
+/**
+ * Options for the recovery of the samples missed by an advanced Subscriber
+ * @prop {TimeDuration=} periodicQueries - Period of the queries to the publishers, the recovery is triggered by their heartbeat if not set
+ */
+export interface RecoveryOptions {
+    periodicQueries?: TimeDuration,
+}
+
+/**
+ * Options for an advanced Subscriber
+ * @prop {HistoryOptions=} history - Retrieves the samples published before the declaration, if set
+ * @prop {RecoveryOptions=} recovery - Retrieves the missed samples, if set
+ * @prop {TimeDuration=} queryTimeout - Timeout of the queries for the history and the recovery
+ * @prop {boolean=} subscriberDetection - Lets the advanced publishers detect this subscriber
+ * @prop {function=} sampleMissHandler - Called with the samples of a publisher which were missed and could not be recovered
+ */
+export interface AdvancedSubscriberOptions extends SubscriberOptions {
+    history?: HistoryOptions,
+    recovery?: RecoveryOptions,
+    queryTimeout?: TimeDuration,
+    subscriberDetection?: boolean,
+    sampleMissHandler?: (miss: SampleMiss) => void,
+}
+
 /**
  * Options for a Querier
  * @prop {CongestionControl=} congestion_control - Congestion control applied when routing this Querier queries
@@ -205,7 +265,7 @@ export class Session {
      */
 
     static async open(config: Config): Promise<Session> {
-        let inner = await SessionInner.open(config.locator, config.messageResponseTimeoutMs);
+        let inner = await SessionInner.open(config.locator, config.messageResponseTimeoutMs, config.resume);
         return new Session(inner);
     }
 
@@ -375,6 +435,48 @@ export class Session {
         return new Subscriber(this.inner, SubscriberKind.Subscriber, subscriberId, keyexpr, receiver);
     }
 
+    /**
+     * Declares an advanced subscriber, which can retrieve the samples published before its declaration,
+     * and recover or be notified of the samples it missed.
+     * Requires a remote api plugin supporting protocol version 3.
+     *
+     * @param {IntoKeyExpr} intoKeyExpr - key expression to subscribe to
+     * @param {AdvancedSubscriberOptions} subscriberOpts - optional additional parameters to pass to subscriber declaration
+     *
+     * @returns Subscriber
+     */
+    async declareAdvancedSubscriber(
+        intoKeyExpr: IntoKeyExpr,
+        subscriberOpts?: AdvancedSubscriberOptions
+    ): Promise<Subscriber> {
+        const handler = subscriberOpts?.handler ?? new FifoChannel<Sample>(256);
+        const keyexpr = new KeyExpr(intoKeyExpr);
+        let [callback, drop, receiver] = intoCbDropReceiver(handler);
+
+        const history = subscriberOpts?.history;
+        const recovery = subscriberOpts?.recovery;
+        const properties: AdvancedSubscriberProperties = {
+            keyexpr,
+            allowedOrigin: subscriberOpts?.allowedOrigin ?? Locality.DEFAULT,
+            history: history ? {
+                detectLatePublishers: history.detectLatePublishers ?? false,
+                maxSamples: history.maxSamples,
+                maxAgeMs: history.maxAge ? Duration.milliseconds.from(history.maxAge) : undefined,
+            } : undefined,
+            recovery: recovery ? {
+                periodicQueriesMs: recovery.periodicQueries ? Duration.milliseconds.from(recovery.periodicQueries) : undefined,
+            } : undefined,
+            queryTimeoutMs: subscriberOpts?.queryTimeout ? Duration.milliseconds.from(subscriberOpts.queryTimeout) : undefined,
+            subscriberDetection: subscriberOpts?.subscriberDetection ?? false,
+        };
+        const subscriberId = await this.inner.declareAdvancedSubscriber(
+            properties,
+            { callback, drop },
+            subscriberOpts?.sampleMissHandler
+        );
+        return new Subscriber(this.inner, SubscriberKind.Subscriber, subscriberId, keyexpr, receiver);
+    }
+
     /**
      * Obtain a Liveliness struct tied to this Zenoh Session.
      * 
@@ -431,21 +533,39 @@ export class Session {
         intoKeyExpr: IntoKeyExpr,
         publisherOpts?: PublisherOptions
     ): Promise<Publisher> {
-        let publisherProperties = {
-            keyexpr: new KeyExpr(intoKeyExpr),
-            encoding: publisherOpts?.encoding ? Encoding.from(publisherOpts.encoding) : Encoding.default(),
-            qos: new Qos(
-                publisherOpts?.priority ?? Priority.DEFAULT,
-                publisherOpts?.congestionControl ?? CongestionControl.DEFAULT_PUSH,
-                publisherOpts?.express ?? false,
-                publisherOpts?.reliability ?? Reliability.DEFAULT,
-                publisherOpts?.allowedDestination ?? Locality.DEFAULT
-            )
-        };
+        let publisherProperties = intoPublisherProperties(intoKeyExpr, publisherOpts);
         const publisherId = await this.inner.declarePublisher(publisherProperties);
         return new Publisher(this.inner, publisherId, publisherProperties);
     }
 
+    /**
+    * Declares a new advanced Publisher, whose samples can be cached for the late joiners
+    * and the recovery of the samples missed by the advanced subscribers.
+    * Requires a remote api plugin supporting protocol version 3.
+    *
+    * @param {IntoKeyExpr} intoKeyExpr - Publisher's key expression
+    * @param {AdvancedPublisherOptions=} publisherOpts - Optional additional settings for an advanced Publisher [AdvancedPublisherOptions]
+    * @returns Publisher
+    */
+    async declareAdvancedPublisher(
+        intoKeyExpr: IntoKeyExpr,
+        publisherOpts?: AdvancedPublisherOptions
+    ): Promise<Publisher> {
+        const sampleMissDetection = publisherOpts?.sampleMissDetection;
+        let publisherProperties: AdvancedPublisherProperties = {
+            ...intoPublisherProperties(intoKeyExpr, publisherOpts),
+            cacheMaxSamples: publisherOpts?.cacheMaxSamples,
+            sampleMissDetection: sampleMissDetection ? {
+                heartbeat: sampleMissDetection.heartbeat ?? HeartbeatMode.NONE,
+                periodMs: sampleMissDetection.period ? Duration.milliseconds.from(sampleMissDetection.period) : 0,
+            } : undefined,
+            publisherDetection: publisherOpts?.publisherDetection ?? false,
+            publisherDetectionMetadata: publisherOpts?.publisherDetectionMetadata ? new KeyExpr(publisherOpts.publisherDetectionMetadata) : undefined,
+        };
+        const publisherId = await this.inner.declareAdvancedPublisher(publisherProperties);
+        return new Publisher(this.inner, publisherId, publisherProperties);
+    }
+
     /**
     * Declares a Querier 
     * 
@@ -489,6 +609,20 @@ export class Session {
 /**
  *  Function to open a Zenoh session
  */
+function intoPublisherProperties(intoKeyExpr: IntoKeyExpr, publisherOpts?: PublisherOptions): PublisherProperties {
+    return {
+        keyexpr: new KeyExpr(intoKeyExpr),
+        encoding: publisherOpts?.encoding ? Encoding.from(publisherOpts.encoding) : Encoding.default(),
+        qos: new Qos(
+            publisherOpts?.priority ?? Priority.DEFAULT,
+            publisherOpts?.congestionControl ?? CongestionControl.DEFAULT_PUSH,
+            publisherOpts?.express ?? false,
+            publisherOpts?.reliability ?? Reliability.DEFAULT,
+            publisherOpts?.allowedDestination ?? Locality.DEFAULT
+        )
+    };
+}
+
 export async function open(config: Config): Promise<Session> {
     return await Session.open(config);
 }
@@ -15,11 +15,11 @@
 
 import { ZBytesDeserializer, ZBytesSerializer } from "./ext/index.js";
 import { KeyExpr } from "./key_expr.js";
-import { DeclareLivelinessSubscriber, DeclareLivelinessToken, DeclarePublisher, DeclareQuerier, DeclareQueryable, DeclareSubscriber, Delete, deserializeHeader, Get, GetProperties, GetSessionInfo, GetTimestamp, GetTransports, GetLinks, InQuery, InRemoteMessageId, InReply, InSample, InTransportEventUpdate, InLinkEventUpdate, LivelinessGet, LivelinessGetProperties, LivelinessSubscriberProperties, MatchingStatusUpdate, OutMessageInterface, OutDeclareTransportEventsListener, OutUndeclareTransportEventsListener, OutDeclareLinkEventsListener, OutUndeclareLinkEventsListener, Ping, PublisherDeclareMatchingListener, PublisherDelete, PublisherGetMatchingStatus, PublisherProperties, PublisherPut, Put, QuerierDeclareMatchingListener, QuerierGet, QuerierGetMatchingStatus, QuerierGetProperties, QuerierProperties, QueryableProperties, QueryResponseFinal, ReplyDel, ReplyErr, ReplyOk, ResponseError, ResponseLinks, ResponseMatchingStatus, ResponseOk, ResponsePing, ResponseSessionInfo, ResponseTimestamp, ResponseTransports, serializeHeader, SubscriberProperties, UndeclareLivelinessSubscriber, UndeclareLivelinessToken, UndeclareMatchingListener, UndeclarePublisher, UndeclareQuerier, UndeclareQueryable, UndeclareSubscriber } from "./message.js";
+import { ADVANCED_PUB_SUB_PROTOCOL_VERSION, AdvancedPublisherProperties, AdvancedSubscriberProperties, Batch, decompressMessage, DeclareAdvancedPublisher, DeclareAdvancedSubscriber, InSampleMiss, DeclareLivelinessSubscriber, FEATURE_BATCHING, FEATURE_COMPRESSION, FEATURE_RESUMPTION, Hello, HelloAck, LEGACY_PROTOCOL_VERSION, PROTOCOL_VERSION, DeclareLivelinessToken, DeclarePublisher, DeclareQuerier, DeclareQueryable, DeclareSubscriber, Delete, deserializeHeader, Get, GetProperties, GetSessionInfo, GetTimestamp, GetTransports, GetLinks, InQuery, InRemoteMessageId, InReply, InSample, InTransportEventUpdate, InLinkEventUpdate, LivelinessGet, LivelinessGetProperties, LivelinessSubscriberProperties, MatchingStatusUpdate, OutMessageInterface, OutDeclareTransportEventsListener, OutUndeclareTransportEventsListener, OutDeclareLinkEventsListener, OutUndeclareLinkEventsListener, Ping, PublisherDeclareMatchingListener, PublisherDelete, PublisherGetMatchingStatus, PublisherProperties, PublisherPut, Put, QuerierDeclareMatchingListener, QuerierGet, QuerierGetMatchingStatus, QuerierGetProperties, QuerierProperties, QueryableProperties, QueryResponseFinal, ReplyDel, ReplyErr, ReplyOk, ResponseError, ResponseLinks, ResponseMatchingStatus, ResponseOk, ResponsePing, ResponseSessionInfo, ResponseTimestamp, ResponseTransports, ResumeToken, serializeHeader, SubscriberProperties, UndeclareLivelinessSubscriber, UndeclareLivelinessToken, UndeclareMatchingListener, UndeclarePublisher, UndeclareQuerier, UndeclareQueryable, UndeclareSubscriber } from "./message.js";
 import { Query, Reply } from "./query.js";
 import { Closure } from "./closure.js";
 import { RemoteLink } from "./link.js";
-import { Sample } from "./sample.js";
+import { Sample, SampleMiss } from "./sample.js";
 import { SessionInfo } from "./session.js";
 import { Timestamp } from "./timestamp.js";
 import { MatchingStatus } from "./matching.js";
@@ -66,6 +66,15 @@ class IdSource {
     }
 }
 
+// The query parameter of the websocket url by which the session opts in to resumption:
+// empty to be issued a resume token, or set to the token previously issued to resume the session.
+const RESUME_QUERY_PARAMETER = "resume";
+
+function withQueryParameter(locator: string, name: string, value: string): string {
+    const separator = locator.includes("?") ? "&" : "?";
+    return `${locator}${separator}${name}=${encodeURIComponent(value)}`;
+}
+
 type OnResponseReceivedCallback = (msg: [InRemoteMessageId, ZBytesDeserializer]) => void;
 
 export class SessionInner {
@@ -73,8 +82,15 @@ export class SessionInner {
 
     private link: RemoteLink;
     private id: string = "";
+    private readonly locator: string;
+    // the token to present to resume the session, if it opted in to resumption
+    private resumeToken: string | undefined = undefined;
+    private receiving: Promise<void> = Promise.resolve();
+    // the version of the protocol negotiated with the remote api plugin
+    private protocolVersion_: number = LEGACY_PROTOCOL_VERSION;
 
     private subscribers: Map<SubscriberId, Closure<Sample>> = new Map<SubscriberId, Closure<Sample>>();
+    private sampleMissListeners: Map<SubscriberId, (miss: SampleMiss) => void> = new Map<SubscriberId, (miss: SampleMiss) => void>();
     private queryables: Map<QueryableId, Closure<Query>> = new Map<QueryableId, Closure<Query>>();
     private gets: Map<GetId, Closure<Reply>> = new Map<GetId, Closure<Reply>>();
     private matchingListeners: Map<MatchingListenerId, Closure<MatchingStatus>> = new Map<MatchingListenerId, Closure<MatchingStatus>>();
@@ -84,16 +100,57 @@ export class SessionInner {
     private nextMessageId: number = 0;
     private readonly messageResponseTimeoutMs: number;
 
-    private constructor(link: RemoteLink, messageResponseTimeoutMs: number) {
+    private constructor(link: RemoteLink, locator: string, messageResponseTimeoutMs: number, resume: boolean) {
         this.link = link;
+        this.locator = locator;
         this.messageResponseTimeoutMs = messageResponseTimeoutMs;
-        this.link.onmessage((msg: any) => { 
-            try {
-                this.onMessageReceived(msg);
-            } catch (e) {
-                console.warn(e);
-            }
+        this.attach(resume);
+    }
+
+    private attach(resume: boolean) {
+        this.link.onmessage((msg: Uint8Array) => {
+            // decompressing is asynchronous, the messages are still handled in the order they are received
+            this.receiving = this.receiving
+                .then(async () => this.onMessageReceived(await decompressMessage(msg)))
+                .catch((e) => console.warn(e));
         });
+        if (resume) {
+            this.link.onclose(() => {
+                this.resume().catch((e) => console.warn(`Failed to resume session ${this.id}: ${e}`));
+            });
+        }
+    }
+
+    // Reconnects with the resume token, the plugin keeping the declarations of the session
+    // and the messages for it while it is disconnected, for a grace period.
+    private async resume() {
+        if (this.isClosed_ || this.resumeToken == undefined) {
+            return;
+        }
+        console.warn(`Resuming session ${this.id}`);
+        this.link = await RemoteLink.new(withQueryParameter(this.locator, RESUME_QUERY_PARAMETER, this.resumeToken));
+        this.attach(true);
+        await this.hello(true);
+    }
+
+    // Negotiates the version of the protocol and the features enabled on the connection.
+    // The plugins which predate the negotiation don't answer, they speak the legacy version.
+    private async hello(resume: boolean) {
+        let features = FEATURE_COMPRESSION | FEATURE_BATCHING;
+        if (resume) {
+            features |= FEATURE_RESUMPTION;
+        }
+        try {
+            const ack = await this.sendRequest(
+                new Hello(PROTOCOL_VERSION, LEGACY_PROTOCOL_VERSION, features),
+                InRemoteMessageId.HelloAck,
+                HelloAck.deserialize
+            );
+            this.protocolVersion_ = ack.version;
+        } catch (e) {
+            console.warn(`Protocol version negotiation failed, using version ${LEGACY_PROTOCOL_VERSION}: ${e}`);
+            this.protocolVersion_ = LEGACY_PROTOCOL_VERSION;
+        }
     }
 
     private onMessageReceived(msg: Uint8Array) {
@@ -139,6 +196,15 @@ export class SessionInner {
                     }
                     break;
                 }
+                case InRemoteMessageId.InSampleMiss: {
+                    const m = InSampleMiss.deserialize(deserializer);
+                    // ignored by the subscribers declared without a sample miss handler
+                    let listener = this.sampleMissListeners.get(m.subscriberId);
+                    if (listener != undefined) {
+                        listener(m.miss);
+                    }
+                    break;
+                }
                 case InRemoteMessageId.QueryResponseFinal: {
                     const q = QueryResponseFinal.deserialize(deserializer);
                     let get = this.gets.get(q.queryId);
@@ -180,6 +246,25 @@ export class SessionInner {
                     }
                     break;
                 }
+                case InRemoteMessageId.Batch: {
+                    const b = Batch.deserialize(deserializer);
+                    for (const m of b.messages) {
+                        try {
+                            this.onMessageReceived(m);
+                        } catch (e) {
+                            console.warn(e);
+                        }
+                    }
+                    break;
+                }
+                case InRemoteMessageId.ResumeToken: {
+                    const t = ResumeToken.deserialize(deserializer);
+                    if (this.resumeToken != undefined && !t.resumed) {
+                        console.warn(`Session ${this.id} could not be resumed, its declarations were lost`);
+                    }
+                    this.resumeToken = t.token;
+                    break;
+                }
                 default: throw new Error(`Received unexpected message type ${messageId}`);
             }
         }
@@ -227,9 +312,10 @@ export class SessionInner {
         return await this.sendRequest(new Ping, InRemoteMessageId.ResponsePing, ResponsePing.deserialize);
     }
 
-    static async open(locator: string, messageResponseTimeoutMs: number): Promise<SessionInner> {
-        let link = await RemoteLink.new(locator);
-        let session =  new SessionInner(link, messageResponseTimeoutMs);
+    static async open(locator: string, messageResponseTimeoutMs: number, resume: boolean = false): Promise<SessionInner> {
+        let link = await RemoteLink.new(resume ? withQueryParameter(locator, RESUME_QUERY_PARAMETER, "") : locator);
+        let session =  new SessionInner(link, locator, messageResponseTimeoutMs, resume);
+        await session.hello(resume);
         session.id = (await session.ping()).uuid; // verify connection
         console.log(`Successfully opened session with id: ${session.id}`);
         return session;
@@ -245,6 +331,17 @@ export class SessionInner {
         return publisherId;
     }
 
+    async declareAdvancedPublisher(info: AdvancedPublisherProperties): Promise<PublisherId> {
+        this.checkProtocolVersion(ADVANCED_PUB_SUB_PROTOCOL_VERSION, "Advanced publishers");
+        let publisherId = IdSource.get<PublisherId>();
+        await this.sendRequest(
+            new DeclareAdvancedPublisher(publisherId, info),
+            InRemoteMessageId.ResponseOk,
+            ResponseOk.deserialize
+        );
+        return publisherId;
+    }
+
     async undeclarePublisher(publisherId: PublisherId) {
         await this.sendRequest(
             new UndeclarePublisher(publisherId), 
@@ -269,7 +366,29 @@ export class SessionInner {
         return subscriberId;
     }
 
+    async declareAdvancedSubscriber(info: AdvancedSubscriberProperties, closure: Closure<Sample>, onMiss?: (miss: SampleMiss) => void): Promise<SubscriberId> {
+        this.checkProtocolVersion(ADVANCED_PUB_SUB_PROTOCOL_VERSION, "Advanced subscribers");
+        let subscriberId = IdSource.get<SubscriberId>();
+        this.subscribers.set(subscriberId, closure);
+        if (onMiss != undefined) {
+            this.sampleMissListeners.set(subscriberId, onMiss);
+        }
+        try {
+            await this.sendRequest(
+                new DeclareAdvancedSubscriber(subscriberId, info),
+                InRemoteMessageId.ResponseOk,
+                ResponseOk.deserialize
+            );
+        } catch (error) {
+            this.subscribers.delete(subscriberId);
+            this.sampleMissListeners.delete(subscriberId);
+            throw error;
+        }
+        return subscriberId;
+    }
+
     async undeclareSubscriber(subscriberId: SubscriberId) {
+        this.sampleMissListeners.delete(subscriberId);
         const subscriber = this.subscribers.get(subscriberId);
         if (subscriber == undefined) {
             new Error (`Unknown subscriber id: ${subscriberId}`)
@@ -623,6 +742,7 @@ export class SessionInner {
             s[1].drop();
         }
         this.subscribers.clear();
+        this.sampleMissListeners.clear();
 
         for (let g of this.gets) {
             g[1].drop();
@@ -656,6 +776,16 @@ export class SessionInner {
         return this.isClosed_;
     }
 
+    protocolVersion(): number {
+        return this.protocolVersion_;
+    }
+
+    private checkProtocolVersion(version: number, feature: string) {
+        if (this.protocolVersion_ < version) {
+            throw new Error(`${feature} require protocol version ${version}, version ${this.protocolVersion_} was negotiated with the remote api plugin`);
+        }
+    }
+
     cancelQuery(queryId: GetId) {
         let get = this.gets.get(queryId);
         if (get != undefined) {